@@ -0,0 +1,14 @@
+#![no_main]
+
+use code_advent_2023::common::Input;
+use code_advent_2023::day2::Day2;
+use code_advent_2023::solution::Puzzle;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let input = Input::from_text(text);
+        let _ = Day2::parse_part1(&input);
+        let _ = Day2::parse_part2(&input);
+    }
+});
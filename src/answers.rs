@@ -0,0 +1,103 @@
+//! Expected answers used by `--check` to catch regressions.
+//!
+//! The file is TOML, with a table per day keyed by input name, e.g.
+//!
+//! ```toml
+//! [day7]
+//! "inputs/day07.txt" = { part1 = 250254244, part2 = "250087440" }
+//! ```
+//!
+//! Input names are matched exactly as the runner reports them, so they are paths relative to
+//! wherever the binary is run from. Answers may be written as integers or strings.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use serde::Deserialize;
+use crate::error::{Error, Result};
+use crate::solution::Answer;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PartAnswers {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    expected: HashMap<(u16, u16, String), Answer>,
+}
+
+fn answer_value(value: toml::Value) -> std::result::Result<Answer, String> {
+    match value {
+        toml::Value::String(s) => Ok(Answer::Text(s)),
+        toml::Value::Integer(n) => Ok(n.into()),
+        other => Err(format!("answers must be strings or integers, found {}", other.type_str())),
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers> {
+        let name = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|source| Error::Io {
+            name: name.clone(),
+            source,
+        })?;
+        Answers::parse(&text).map_err(|message| Error::Config(format!("{}: {}", name, message)))
+    }
+
+    pub fn parse(text: &str) -> std::result::Result<Answers, String> {
+        let days: BTreeMap<String, BTreeMap<String, PartAnswers>> = toml::from_str(text).map_err(|err| err.to_string())?;
+
+        let mut expected = HashMap::new();
+        for (day_key, inputs) in days {
+            let day: u16 = day_key.strip_prefix("day")
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| format!("expected a table named like `day7`, found `{}`", day_key))?;
+            for (input, parts) in inputs {
+                for (part, value) in [(1, parts.part1), (2, parts.part2)] {
+                    if let Some(value) = value {
+                        expected.insert((day, part, input.clone()), answer_value(value)?);
+                    }
+                }
+            }
+        }
+        Ok(Answers { expected })
+    }
+
+    pub fn expected(&self, day: u16, part: u16, input: &str) -> Option<&Answer> {
+        self.expected.get(&(day, part, input.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Fail { expected: Answer, actual: Answer },
+    /// No stored answer for this day/part/input.
+    Missing,
+    /// The part is a stub and nothing was expected of it yet.
+    Unsolved,
+    /// The solver itself failed, so there's nothing to compare.
+    Error(String),
+}
+
+impl CheckStatus {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, CheckStatus::Fail { .. } | CheckStatus::Error(_))
+    }
+}
+
+pub fn check(expected: Option<&Answer>, actual: &std::result::Result<Answer, String>) -> CheckStatus {
+    match (expected, actual) {
+        (_, Err(message)) => CheckStatus::Error(message.clone()),
+        (None, Ok(Answer::Unsolved)) => CheckStatus::Unsolved,
+        (None, Ok(_)) => CheckStatus::Missing,
+        (Some(expected), Ok(actual)) if expected.matches(actual) => CheckStatus::Pass,
+        (Some(expected), Ok(actual)) => CheckStatus::Fail {
+            expected: expected.clone(),
+            actual: actual.clone(),
+        },
+    }
+}
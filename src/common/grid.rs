@@ -0,0 +1,224 @@
+//! A dense 2D grid addressed by `(row, column)`, for the many puzzles drawn on a character map.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A `(row, column)` position in a [`Grid`].
+pub type Pos = (usize, usize);
+
+/// Up, right, down, left, as `(row, column)` offsets.
+pub const DIRECTIONS4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// The four orthogonal directions followed by the four diagonals.
+pub const DIRECTIONS8: [(isize, isize); 8] = [
+    (-1, 0), (0, 1), (1, 0), (0, -1),
+    (-1, -1), (-1, 1), (1, 1), (1, -1),
+];
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, or returns `None` if they aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let columns = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != columns) {
+            return None;
+        }
+        Some(Grid {
+            rows: rows.len(),
+            columns,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn contains(&self, (row, column): Pos) -> bool {
+        row < self.rows && column < self.columns
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.0 * self.columns + pos.1])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.columns + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `pos` by a signed `(row, column)` offset, or returns `None` if that leaves the grid.
+    pub fn offset(&self, (row, column): Pos, (d_row, d_column): (isize, isize)) -> Option<Pos> {
+        let pos = (row.checked_add_signed(d_row)?, column.checked_add_signed(d_column)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// The in-bounds positions orthogonally adjacent to `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS4.into_iter().filter_map(move |delta| self.offset(pos, delta))
+    }
+
+    /// The in-bounds positions orthogonally or diagonally adjacent to `pos`.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS8.into_iter().filter_map(move |delta| self.offset(pos, delta))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.columns, "column {} out of range for a grid {} wide", column, self.columns);
+        self.cells.iter().skip(column).step_by(self.columns)
+    }
+
+    /// Every position in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |row| (0..columns).map(move |column| (row, column)))
+    }
+
+    /// Every cell with its position, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The first position, in row-major order, whose cell matches `predicate`.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter().find_map(|(pos, cell)| predicate(cell).then_some(pos))
+    }
+
+    /// Every position whose cell matches `predicate`, in row-major order.
+    pub fn find_all<'a>(&'a self, predicate: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = Pos> + 'a {
+        self.iter().filter_map(move |(pos, cell)| predicate(cell).then_some(pos))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            rows: self.rows,
+            columns: self.columns,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn rebuild(&self, rows: usize, columns: usize, source: impl Fn(Pos) -> Pos) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..rows).flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|pos| self[source(pos)].clone())
+            .collect();
+        Grid { rows, columns, cells }
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rebuild(self.columns, self.rows, |(row, column)| (column, row))
+    }
+
+    /// Turns the grid a quarter turn clockwise, so the first column becomes the first row reversed.
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let rows = self.rows;
+        self.rebuild(self.columns, self.rows, |(row, column)| (rows - 1 - column, row))
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let columns = self.columns;
+        self.rebuild(self.columns, self.rows, |(row, column)| (column, columns - 1 - row))
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{:?} out of range for a {}x{} grid", pos, self.rows, self.columns))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (rows, columns) = (self.rows, self.columns);
+        self.get_mut(pos).unwrap_or_else(|| panic!("{:?} out of range for a {}x{} grid", pos, rows, columns))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.rows()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(text: &str) -> Grid<char> {
+        Grid::from_rows(text.lines().map(|line| line.chars().collect()).collect()).unwrap()
+    }
+
+    fn text(grid: &Grid<char>) -> String {
+        grid.rows().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+        assert_eq!(Grid::<u8>::from_rows(Vec::new()).unwrap().num_rows(), 0);
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let g = grid("abc\ndef");
+        assert_eq!(g.neighbors4((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+        assert_eq!(g.neighbors8((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(g.neighbors8((1, 1)).count(), 5);
+        assert_eq!(g.offset((1, 2), (0, 1)), None);
+        assert_eq!(g.offset((1, 2), (-1, -2)), Some((0, 0)));
+    }
+
+    #[test]
+    fn slices_and_finds() {
+        let g = grid("abc\ndbf");
+        assert_eq!(g.row(1), ['d', 'b', 'f']);
+        assert_eq!(g.column(2).collect::<String>(), "cf");
+        assert_eq!(g.find(|c| *c == 'b'), Some((0, 1)));
+        assert_eq!(g.find_all(|c| *c == 'b').collect::<Vec<_>>(), [(0, 1), (1, 1)]);
+        assert_eq!(g.find(|c| *c == 'z'), None);
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let g = grid("abc\ndef");
+        assert_eq!(text(&g.transpose()), "ad\nbe\ncf");
+        assert_eq!(text(&g.rotate_clockwise()), "da\neb\nfc");
+        assert_eq!(text(&g.rotate_counterclockwise()), "cf\nbe\nad");
+        assert_eq!(g.rotate_clockwise().rotate_counterclockwise(), g);
+    }
+}
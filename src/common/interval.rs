@@ -0,0 +1,318 @@
+//! Sets of half-open integer ranges, for puzzles that push whole ranges of values through a
+//! transformation rather than one value at a time.
+
+use std::fmt;
+use std::ops::Range;
+
+/// An integer type an [`IntervalSet`] can hold. Implemented for `u64` and `i64`.
+pub trait Bound: Copy + Ord + fmt::Debug {
+    /// `end - start`, for `start <= end`. Always fits, even across the whole of `i64`.
+    fn distance(start: Self, end: Self) -> u64;
+
+    /// `self + delta`, or `None` if that leaves the type's range. `delta` is an `i128` so it can
+    /// carry the difference between any two values of the type.
+    fn checked_shift(self, delta: i128) -> Option<Self>;
+}
+
+impl Bound for u64 {
+    fn distance(start: u64, end: u64) -> u64 {
+        end - start
+    }
+
+    fn checked_shift(self, delta: i128) -> Option<u64> {
+        u64::try_from((self as i128).checked_add(delta)?).ok()
+    }
+}
+
+impl Bound for i64 {
+    fn distance(start: i64, end: i64) -> u64 {
+        end.abs_diff(start)
+    }
+
+    fn checked_shift(self, delta: i128) -> Option<i64> {
+        i64::try_from((self as i128).checked_add(delta)?).ok()
+    }
+}
+
+/// A set of integers stored as half-open ranges that are kept sorted, non-empty and separated by
+/// at least one missing value, so equal sets always have equal ranges.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    /// The union of `ranges`, which may be empty, unsorted or overlapping.
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<T>>) -> IntervalSet<T> {
+        let mut sorted: Vec<Range<T>> = ranges.into_iter().filter(|range| range.start < range.end).collect();
+        sorted.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        IntervalSet { ranges: merged }
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        let ranges = std::mem::take(&mut self.ranges);
+        *self = IntervalSet::from_ranges(ranges.into_iter().chain([range]));
+    }
+
+    /// The normalized ranges, in increasing order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges.get(idx).is_some_and(|range| range.start <= value)
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    /// How many values the set holds.
+    pub fn total_len(&self) -> u64 {
+        self.ranges.iter().map(|range| T::distance(range.start, range.end)).sum()
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        IntervalSet::from_ranges(self.ranges.iter().chain(&other.ranges).cloned())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        // pieces of disjoint inputs are already sorted and separated
+        IntervalSet { ranges }
+    }
+
+    /// The values in `self` that aren't in `other`.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            // cuts entirely before this range can't touch any later one either
+            while other.ranges.get(j).is_some_and(|cut| cut.end <= start) {
+                j += 1;
+            }
+            for cut in other.ranges[j..].iter().take_while(|cut| cut.start < range.end) {
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// Splits the set into the values below `at` and the values from `at` up.
+    pub fn split_at(&self, at: T) -> (IntervalSet<T>, IntervalSet<T>) {
+        let below = self.ranges.iter().filter(|range| range.start < at).map(|range| range.start..range.end.min(at));
+        let above = self.ranges.iter().filter(|range| range.end > at).map(|range| range.start.max(at)..range.end);
+        (IntervalSet { ranges: below.collect() }, IntervalSet { ranges: above.collect() })
+    }
+
+    /// The ranges of the set, cut wherever one straddles a value in `boundaries`, so each piece lies
+    /// entirely on one side of every boundary.
+    pub fn split(&self, boundaries: impl IntoIterator<Item = T>) -> Vec<Range<T>> {
+        let mut boundaries: Vec<T> = boundaries.into_iter().collect();
+        boundaries.sort();
+        boundaries.dedup();
+
+        let mut pieces = Vec::new();
+        for range in &self.ranges {
+            let first = boundaries.partition_point(|&at| at <= range.start);
+            let mut start = range.start;
+            for &at in boundaries[first..].iter().take_while(|&&at| at < range.end) {
+                pieces.push(start..at);
+                start = at;
+            }
+            pieces.push(start..range.end);
+        }
+        pieces
+    }
+
+    /// Every value moved by `delta`, or `None` if one would leave the type's range. The ranges are
+    /// half-open, so a set can't hold the type's largest value and moving a value onto it fails too.
+    pub fn shift(&self, delta: i128) -> Option<IntervalSet<T>> {
+        let ranges = self.ranges.iter()
+            .map(|range| Some(range.start.checked_shift(delta)?..range.end.checked_shift(delta)?))
+            .collect::<Option<_>>()?;
+        Some(IntervalSet { ranges })
+    }
+}
+
+impl<T: Bound> Default for IntervalSet<T> {
+    fn default() -> IntervalSet<T> {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Bound> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> IntervalSet<T> {
+        IntervalSet::from_ranges([range])
+    }
+}
+
+impl<T: Bound> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> IntervalSet<T> {
+        IntervalSet::from_ranges(ranges)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.ranges).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use proptest::prelude::*;
+    use super::*;
+
+    const LIMIT: u64 = 64;
+
+    fn ranges() -> impl Strategy<Value = Vec<Range<u64>>> {
+        prop::collection::vec((0..LIMIT, 0..LIMIT).prop_map(|(a, b)| a..b), 0..6)
+    }
+
+    fn members(set: &IntervalSet<u64>) -> BTreeSet<u64> {
+        (0..LIMIT).filter(|&value| set.contains(value)).collect()
+    }
+
+    fn model(ranges: &[Range<u64>]) -> BTreeSet<u64> {
+        ranges.iter().flat_map(|range| range.clone()).collect()
+    }
+
+    fn is_normalized<T: Bound>(set: &IntervalSet<T>) -> bool {
+        set.ranges().iter().all(|range| range.start < range.end)
+            && set.ranges().windows(2).all(|pair| pair[0].end < pair[1].start)
+    }
+
+    #[test]
+    fn merges_overlapping_and_adjacent_ranges() {
+        let set = IntervalSet::from_ranges([5..8u64, 1..3, 3..4, 7..10, 12..12]);
+        assert_eq!(set.ranges(), [1..4, 5..10]);
+        assert_eq!((set.total_len(), set.min()), (8, Some(1)));
+        assert_eq!(set.split([0, 2, 5, 6, 11]), [1..2, 2..4, 5..6, 6..10]);
+    }
+
+    #[test]
+    fn signed_ranges() {
+        let set = IntervalSet::from_ranges([-10..-2i64, 4..6]);
+        assert_eq!(set.difference(&IntervalSet::from(-5..5)).ranges(), [-10..-5, 5..6]);
+        assert_eq!(set.shift(3).unwrap().ranges(), [-7..1, 7..9]);
+        assert_eq!(set.shift(i64::MAX as i128), None);
+        assert_eq!(IntervalSet::from(i64::MIN..i64::MAX).total_len(), u64::MAX);
+    }
+
+    #[test]
+    fn shifts_further_than_an_i64() {
+        let top = IntervalSet::from(u64::MAX - 10..u64::MAX - 5);
+        assert_eq!(top.shift(-(u64::MAX as i128 - 10)).unwrap(), IntervalSet::from(0..5));
+        assert_eq!(IntervalSet::from(0..5u64).shift(u64::MAX as i128 - 10).unwrap(), top);
+        // the end of the last range can't move past the largest value
+        assert_eq!(top.shift(5).unwrap(), IntervalSet::from(u64::MAX - 5..u64::MAX));
+        assert_eq!(top.shift(6), None);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_a_set_of_values(a in ranges(), b in ranges()) {
+            let (set_a, set_b) = (IntervalSet::from_ranges(a.clone()), IntervalSet::from_ranges(b.clone()));
+            let (model_a, model_b) = (model(&a), model(&b));
+
+            prop_assert!(is_normalized(&set_a));
+            prop_assert_eq!(members(&set_a), model_a.clone());
+            prop_assert_eq!(set_a.total_len(), model_a.len() as u64);
+            prop_assert_eq!(set_a.min(), model_a.first().copied());
+
+            let union = set_a.union(&set_b);
+            prop_assert!(is_normalized(&union));
+            prop_assert_eq!(members(&union), &model_a | &model_b);
+
+            let intersection = set_a.intersection(&set_b);
+            prop_assert!(is_normalized(&intersection));
+            prop_assert_eq!(members(&intersection), &model_a & &model_b);
+
+            let difference = set_a.difference(&set_b);
+            prop_assert!(is_normalized(&difference));
+            prop_assert_eq!(members(&difference), &model_a - &model_b);
+        }
+
+        #[test]
+        fn equal_sets_have_equal_ranges(a in ranges(), b in ranges()) {
+            let (set_a, set_b) = (IntervalSet::from_ranges(a.clone()), IntervalSet::from_ranges(b.clone()));
+            prop_assert_eq!(set_a == set_b, model(&a) == model(&b));
+
+            let mut inserted = IntervalSet::new();
+            for range in a {
+                inserted.insert(range);
+            }
+            prop_assert_eq!(inserted, set_a);
+        }
+
+        #[test]
+        fn splitting_keeps_every_value(a in ranges(), at in 0..LIMIT, boundaries in prop::collection::vec(0..LIMIT, 0..5)) {
+            let set = IntervalSet::from_ranges(a);
+
+            let (below, above) = set.split_at(at);
+            prop_assert!(is_normalized(&below) && is_normalized(&above));
+            prop_assert!(members(&below).iter().all(|&value| value < at));
+            prop_assert!(members(&above).iter().all(|&value| value >= at));
+            prop_assert_eq!(below.union(&above), set.clone());
+
+            let pieces = set.split(boundaries.iter().copied());
+            prop_assert!(pieces.windows(2).all(|pair| pair[0].end <= pair[1].start));
+            let straddles = |piece: &Range<u64>| boundaries.iter().any(|&at| piece.start < at && at < piece.end);
+            prop_assert!(pieces.iter().all(|piece| piece.start < piece.end && !straddles(piece)));
+            prop_assert_eq!(IntervalSet::from_ranges(pieces), set);
+        }
+
+        #[test]
+        fn shifting_moves_every_value(a in ranges(), delta in -(LIMIT as i128)..LIMIT as i128) {
+            let set = IntervalSet::from_ranges(a.clone());
+            match set.shift(delta) {
+                Some(shifted) => {
+                    prop_assert!(is_normalized(&shifted));
+                    prop_assert_eq!(shifted.total_len(), set.total_len());
+                    prop_assert_eq!(shifted.shift(-delta), Some(set));
+                },
+                // only possible when something would go below zero
+                None => prop_assert!(model(&a).iter().any(|&value| (value as i128) + delta < 0)),
+            }
+        }
+    }
+}
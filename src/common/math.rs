@@ -0,0 +1,165 @@
+//! Number theory for the puzzles whose answers are "when do all these cycles line up".
+//!
+//! Everything works on `u64` and does its intermediate arithmetic in `u128`, returning `None`
+//! instead of wrapping when a result doesn't fit.
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or `None` if it overflows `u64`. `lcm(0, n)` is 0.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The gcd of every number in `numbers`; 0 for none.
+pub fn gcd_all(numbers: impl IntoIterator<Item = u64>) -> u64 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// The lcm of every number in `numbers`, or `None` on overflow; 1 for none.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u64>) -> Option<u64> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+/// The prime factors of `n` with their multiplicities, smallest first: 360 gives
+/// `[(2, 3), (3, 2), (5, 1)]`. 0 and 1 have none.
+///
+/// This is trial division, so it is only quick when `n` has no two large prime factors, which is
+/// always the case for cycle lengths that come out of a puzzle input.
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    if n == 0 {
+        return factors;
+    }
+    let mut divisor = 2;
+    while divisor <= n / divisor {
+        let mut exponent = 0;
+        while n.is_multiple_of(divisor) {
+            n /= divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((divisor, exponent));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// `(g, x, y)` with `a*x + b*y = g = gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// The `x` in `0..modulus` with `a*x ≡ 1 (mod modulus)`, or `None` if `a` and `modulus` aren't
+/// coprime.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd((a % modulus) as i128, modulus as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus as i128) as u64)
+}
+
+/// Solves the system `x ≡ residue (mod modulus)` for every `(residue, modulus)` pair. The moduli
+/// needn't be coprime.
+///
+/// Returns `(x, m)` where the solutions are exactly `x + k*m` and `x < m`, or `None` if the
+/// congruences contradict each other, a modulus is 0, or `m` would overflow `u64`. No congruences
+/// at all gives `(0, 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = (u64, u64)>) -> Option<(u64, u64)> {
+    congruences.into_iter().try_fold((0, 1), |(r1, m1), (r2, m2)| {
+        if m2 == 0 {
+            return None;
+        }
+        let r2 = r2 % m2;
+        let g = gcd(m1, m2);
+        let diff = r2 as i128 - r1 as i128;
+        if diff % g as i128 != 0 {
+            return None;
+        }
+
+        let combined = lcm(m1, m2)?;
+        let step = m2 / g;
+        // solve m1*k ≡ diff (mod m2) for k, then x = r1 + m1*k; both products fit in a u128
+        let inverse = mod_inverse(m1 / g, step)? as u128;
+        let k = (diff / g as i128).rem_euclid(step as i128) as u128 * inverse % step as u128;
+        let x = (r1 as u128 + m1 as u128 * k) % combined as u128;
+        Some((x as u64, combined))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+    }
+
+    #[test]
+    fn factorizes_with_multiplicity() {
+        assert_eq!(factorize(360), [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(1), []);
+        assert_eq!(factorize(97), [(97, 1)]);
+        assert_eq!(factorize(4), [(2, 2)]);
+        assert_eq!(factorize(u64::MAX), [(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6700417, 1)]);
+        for n in 1..2000u64 {
+            assert_eq!(factorize(n).iter().map(|&(p, e)| p.pow(e)).product::<u64>(), n);
+        }
+    }
+
+    #[test]
+    fn modular_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 0), None);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non-coprime moduli that agree, and ones that don't
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(5, 0)]), None);
+        assert_eq!(crt([(0, u64::MAX), (0, u64::MAX - 1)]), None);
+
+        // products of moduli this size only fit in the u128 intermediates
+        let prime = (1u64 << 31) - 1;
+        let (x, m) = crt([(3, prime), (u64::MAX, 1 << 32)]).unwrap();
+        assert_eq!((x % prime, x % (1 << 32), m), (3, (1 << 32) - 1, prime << 32));
+    }
+}
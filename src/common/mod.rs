@@ -0,0 +1,239 @@
+use std::fs;
+use std::io::{self, BufRead};
+use std::ops::Deref;
+use std::path::Path;
+use crate::error::{Error, ParseError, ParseResult, Result};
+
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod search;
+
+pub use grid::{Grid, Pos};
+
+/// An input line that remembers where it came from. Depending on the accessor that produced it,
+/// `text` is either trimmed or the line exactly as written.
+#[derive(Debug, Clone)]
+pub struct Line {
+    /// 1-based line number in the original input.
+    pub number: usize,
+    pub text: String,
+    raw: String,
+    offset: usize,
+}
+
+impl Line {
+    /// Builds an error pointing at `span`, which should be a slice of this line's text.
+    /// Anything else (e.g. an owned temporary) falls back to pointing at the whole line.
+    pub fn error_at(&self, span: &str, message: impl Into<String>) -> ParseError {
+        let text_start = self.text.as_ptr() as usize;
+        let span_start = span.as_ptr() as usize;
+        let (byte_offset, text) = if span_start >= text_start && span_start + span.len() <= text_start + self.text.len() {
+            (self.offset + (span_start - text_start), span)
+        } else {
+            (self.offset, self.text.as_str())
+        };
+
+        ParseError {
+            file: None,
+            line: self.number,
+            column: self.raw[..byte_offset].chars().count() + 1,
+            text: text.to_string(),
+            message: message.into(),
+            source_line: self.raw.clone(),
+        }
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(&self.text, message)
+    }
+}
+
+impl Deref for Line {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Puzzle input loaded from a file, stdin, an inline string or any reader.
+///
+/// The text is normalized on the way in: a leading UTF-8 byte order mark is dropped and CRLF line
+/// endings become LF, so every accessor sees the same lines whatever editor saved the file.
+#[derive(Debug, Clone)]
+pub struct Input {
+    name: String,
+    text: String,
+}
+
+impl Input {
+    fn new(name: impl Into<String>, text: &str) -> Input {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        Input {
+            name: name.into(),
+            text: text.replace("\r\n", "\n"),
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Input> {
+        let name = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|source| Error::Io {
+            name: name.clone(),
+            source,
+        })?;
+        Ok(Input::new(name, &text))
+    }
+
+    pub fn from_reader(name: &str, mut reader: impl BufRead) -> Result<Input> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).map_err(|source| Error::Io {
+            name: name.to_string(),
+            source,
+        })?;
+        Ok(Input::new(name, &text))
+    }
+
+    pub fn from_text(text: impl AsRef<str>) -> Input {
+        Input::new("<inline>", text.as_ref())
+    }
+
+    pub fn stdin() -> Result<Input> {
+        Input::from_reader("<stdin>", io::stdin().lock())
+    }
+
+    /// Where the input came from: a path, `<stdin>` or `<inline>`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The whole input, after BOM and CRLF normalization.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// `(line number, line)` for every line, without the final empty line a trailing newline
+    /// would otherwise produce.
+    fn numbered_lines(&self) -> impl Iterator<Item = (usize, &str)> {
+        let text = self.text.strip_suffix('\n').unwrap_or(&self.text);
+        let lines = if self.text.is_empty() { None } else { Some(text.split('\n')) };
+        lines.into_iter().flatten().enumerate().map(|(idx, raw)| (idx + 1, raw))
+    }
+
+    /// Every line exactly as written, blank lines and surrounding whitespace included.
+    pub fn raw_lines(&self) -> Vec<Line> {
+        self.numbered_lines().map(|(number, raw)| Line {
+            number,
+            text: raw.to_string(),
+            raw: raw.to_string(),
+            offset: 0,
+        }).collect()
+    }
+
+    /// Every non-blank line, with surrounding whitespace trimmed.
+    pub fn trimmed_lines(&self) -> Vec<Line> {
+        self.numbered_lines().filter_map(|(number, raw)| {
+            let text = raw.trim();
+            if text.is_empty() {
+                None
+            } else {
+                Some(Line {
+                    number,
+                    text: text.to_string(),
+                    raw: raw.to_string(),
+                    offset: raw.len() - raw.trim_start().len(),
+                })
+            }
+        }).collect()
+    }
+
+    /// Runs of non-blank trimmed lines, split wherever one or more blank lines separate them.
+    pub fn sections(&self) -> Vec<Vec<Line>> {
+        let mut sections: Vec<Vec<Line>> = Vec::new();
+        let mut previous: Option<usize> = None;
+        for line in self.trimmed_lines() {
+            match sections.last_mut() {
+                Some(section) if previous == Some(line.number - 1) => section.push(line.clone()),
+                _ => sections.push(vec![line.clone()]),
+            }
+            previous = Some(line.number);
+        }
+        sections
+    }
+
+    /// The rows of a rectangular grid, untrimmed so that leading spaces keep their column. Blank
+    /// lines before and after the grid are ignored.
+    fn grid_rows(&self) -> Vec<Line> {
+        let rows = self.raw_lines();
+        let first = rows.iter().position(|row| !row.trim().is_empty()).unwrap_or(rows.len());
+        let last = rows.iter().rposition(|row| !row.trim().is_empty()).map_or(first, |idx| idx + 1);
+        rows[first..last].to_vec()
+    }
+
+    fn grid<T>(&self, width_of: impl Fn(&str) -> usize, cells: impl Fn(&str) -> Vec<T>) -> ParseResult<Grid<T>> {
+        let rows = self.grid_rows();
+        let width = rows.first().map(|row| width_of(row)).unwrap_or(0);
+        let cells: Vec<Vec<T>> = rows.iter().map(|row| {
+            if width_of(row) != width {
+                return Err(row.error(format!("expected a row of width {}", width)));
+            }
+            Ok(cells(row))
+        }).collect::<ParseResult<_>>()?;
+
+        Ok(Grid::from_rows(cells).expect("rows were checked to have equal width"))
+    }
+
+    /// The input as a grid of chars, erroring on the first row whose width differs from the first.
+    pub fn char_grid(&self) -> ParseResult<Grid<char>> {
+        self.grid(|row| row.chars().count(), |row| row.chars().collect())
+    }
+
+    /// Like [`Input::char_grid`], but in bytes, for puzzles whose grids are plain ASCII.
+    pub fn byte_grid(&self) -> ParseResult<Grid<u8>> {
+        self.grid(str::len, |row| row.bytes().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_bom_and_crlf() {
+        let input = Input::from_text("\u{feff}ab\r\ncd\r\n");
+        assert_eq!(input.text(), "ab\ncd\n");
+        let lines: Vec<String> = input.raw_lines().into_iter().map(|line| line.text).collect();
+        assert_eq!(lines, ["ab", "cd"]);
+    }
+
+    #[test]
+    fn raw_lines_keep_blanks_and_whitespace() {
+        let input = Input::from_text("  a\n\nb  \n");
+        let lines: Vec<(usize, String)> = input.raw_lines().into_iter().map(|line| (line.number, line.text)).collect();
+        assert_eq!(lines, [(1, "  a".to_string()), (2, String::new()), (3, "b  ".to_string())]);
+        assert!(Input::from_text("").raw_lines().is_empty());
+    }
+
+    #[test]
+    fn sections_split_on_blank_lines() {
+        let input = Input::from_text("\na\nb\n\n  \n\nc\n\n");
+        let sections: Vec<Vec<(usize, String)>> = input.sections().into_iter()
+            .map(|section| section.into_iter().map(|line| (line.number, line.text)).collect())
+            .collect();
+        assert_eq!(sections, [
+            vec![(2, "a".to_string()), (3, "b".to_string())],
+            vec![(7, "c".to_string())],
+        ]);
+    }
+
+    #[test]
+    fn grids_keep_leading_spaces() {
+        let grid = Input::from_text("\n .#\n#. \n\n").char_grid().unwrap();
+        assert_eq!(grid.row(0), [' ', '.', '#']);
+        assert_eq!(grid.row(1), ['#', '.', ' ']);
+
+        let err = Input::from_text("ab\r\nabc\r\n").byte_grid().unwrap_err();
+        assert_eq!((err.line, err.message.as_str()), (2, "expected a row of width 2"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Helpers for the input shapes that keep coming back: labels followed by number lists, `key: a, b;
+//! c` records, `K = (L, R)` tables and fixed-width names.
+//!
+//! Every helper takes the [`Line`] being parsed along with a slice of its text, so a failure points
+//! at the exact span that was wrong.
+
+use std::str::FromStr;
+use crate::common::Line;
+use crate::error::ParseResult;
+
+/// Parses `s` as a number, pointing the error at `s` within `line`.
+pub fn number<T: FromStr>(line: &Line, s: &str) -> ParseResult<T> {
+    s.parse().map_err(|_| line.error_at(s, format!("expected a number, found `{}`", s)))
+}
+
+/// Parses a whitespace-separated list of numbers, e.g. `41 48 83`.
+pub fn numbers<T: FromStr>(line: &Line, s: &str) -> ParseResult<Vec<T>> {
+    s.split_whitespace().map(|n| number(line, n)).collect()
+}
+
+/// Splits `s` at the first `delimiter`, or reports `expected` against `s`.
+pub fn split_pair<'a>(line: &Line, s: &'a str, delimiter: &str, expected: &str) -> ParseResult<(&'a str, &'a str)> {
+    s.split_once(delimiter).ok_or_else(|| line.error_at(s, expected))
+}
+
+/// The text after `label`, e.g. `seeds:` in `seeds: 79 14 55 13`.
+pub fn labeled<'a>(line: &Line, s: &'a str, label: &str) -> ParseResult<&'a str> {
+    s.strip_prefix(label).ok_or_else(|| line.error_at(s, format!("expected `{}`", label)))
+}
+
+/// The numbers after `label`, e.g. `[79, 14, 55, 13]` from `seeds: 79 14 55 13`.
+pub fn labeled_numbers<T: FromStr>(line: &Line, s: &str, label: &str) -> ParseResult<Vec<T>> {
+    numbers(line, labeled(line, s, label)?)
+}
+
+/// The number in a `<label> <n>` header such as `Game 3` or `Card  12`.
+pub fn numbered<T: FromStr>(line: &Line, s: &str, label: &str) -> ParseResult<T> {
+    match s.split_whitespace().collect::<Vec<&str>>()[..] {
+        [found, n] if found == label => number(line, n),
+        _ => Err(line.error_at(s, format!("expected `{} N`", label))),
+    }
+}
+
+/// Splits `s` into the records between `delimiter`s, trimming each one, as in `3 blue, 4 red`.
+pub fn delimited<'a>(s: &'a str, delimiter: &'a str) -> impl Iterator<Item = &'a str> {
+    s.split(delimiter).map(str::trim)
+}
+
+/// The text between `open` and `close`, e.g. `BBB, CCC` from `(BBB, CCC)`.
+pub fn enclosed<'a>(line: &Line, s: &'a str, open: char, close: char) -> ParseResult<&'a str> {
+    s.strip_prefix(open).and_then(|inner| inner.strip_suffix(close))
+        .ok_or_else(|| line.error_at(s, format!("expected `{}...{}`", open, close)))
+}
+
+/// A name of exactly `N` bytes, such as the three-letter nodes of day 8.
+pub fn fixed_key<const N: usize>(line: &Line, s: &str) -> ParseResult<[u8; N]> {
+    s.as_bytes().try_into().map_err(|_| line.error_at(s, format!("expected a {} character name", N)))
+}
+
+/// Every integer in free text, each with an optional leading `-`: `x=-3, y=12` gives `[-3, 12]`.
+pub fn integers<T: FromStr>(line: &Line, s: &str) -> ParseResult<Vec<T>> {
+    let bytes = s.as_bytes();
+    let mut found = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let negative = bytes[idx] == b'-' && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit);
+        if !negative && !bytes[idx].is_ascii_digit() {
+            idx += 1;
+            continue;
+        }
+        let start = idx;
+        idx += 1;
+        while bytes.get(idx).is_some_and(u8::is_ascii_digit) {
+            idx += 1;
+        }
+        found.push(number(line, &s[start..idx])?);
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Input;
+
+    fn line(text: &str) -> Line {
+        Input::from_text(text).trimmed_lines().remove(0)
+    }
+
+    #[test]
+    fn labeled_number_lists() {
+        let seeds = line("seeds: 79 14 55 13");
+        assert_eq!(labeled_numbers::<u64>(&seeds, &seeds, "seeds:").unwrap(), [79, 14, 55, 13]);
+
+        let bad = line("seeds: 79 x 55");
+        let err = labeled_numbers::<u64>(&bad, &bad, "seeds:").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (11, "x"));
+        assert!(labeled_numbers::<u64>(&bad, &bad, "time:").is_err());
+    }
+
+    #[test]
+    fn headers_and_records() {
+        let game = line("Game 12: 3 blue, 4 red; 1 green");
+        let (header, draws) = split_pair(&game, &game, ": ", "expected `Game N: ...`").unwrap();
+        assert_eq!(numbered::<u32>(&game, header, "Game").unwrap(), 12);
+        assert!(numbered::<u32>(&game, header, "Card").is_err());
+        let records: Vec<Vec<&str>> = delimited(draws, ";").map(|draw| delimited(draw, ",").collect()).collect();
+        assert_eq!(records, [vec!["3 blue", "4 red"], vec!["1 green"]]);
+    }
+
+    #[test]
+    fn keys_and_brackets() {
+        let node = line("AAA = (BBB, CC)");
+        let (key, value) = split_pair(&node, &node, " = ", "expected `KEY = (LEFT, RIGHT)`").unwrap();
+        assert_eq!(&fixed_key::<3>(&node, key).unwrap(), b"AAA");
+        let (left, right) = split_pair(&node, enclosed(&node, value, '(', ')').unwrap(), ", ", "").unwrap();
+        assert_eq!(left, "BBB");
+        let err = fixed_key::<3>(&node, right).unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (13, "expected a 3 character name"));
+    }
+
+    #[test]
+    fn integers_in_free_text() {
+        let text = line("x=-3, y=12 - 4-5 p-");
+        assert_eq!(integers::<i64>(&text, &text).unwrap(), [-3, 12, 4, -5]);
+        assert!(integers::<u8>(&text, "999").is_err());
+    }
+}
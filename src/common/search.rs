@@ -0,0 +1,309 @@
+//! Graph searches over any node type.
+//!
+//! Graphs are described by a neighbor function rather than a data structure, so the same search
+//! runs over an implicit graph (a closure over a [`Grid`](crate::common::Grid)) or an explicit
+//! adjacency map, e.g. `|node| map[node].clone()`. Everything is iterative, so deep graphs can't
+//! overflow the stack.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// An edge weight. Implemented for the integer types and for `f32`/`f64`.
+pub trait Cost: Copy + PartialOrd + Add<Output = Self> {
+    const ZERO: Self;
+}
+
+macro_rules! cost {
+    ($($t:ty => $zero:expr),*) => {
+        $(impl Cost for $t {
+            const ZERO: Self = $zero;
+        })*
+    };
+}
+
+cost!(u8 => 0, u16 => 0, u32 => 0, u64 => 0, usize => 0, i32 => 0, i64 => 0, f32 => 0.0, f64 => 0.0);
+
+/// What a search reached from its start node.
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    distances: HashMap<N, C>,
+    parents: HashMap<N, N>,
+    order: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Paths<N, C> {
+    fn new(start: N) -> Paths<N, C>
+    where
+        C: Cost,
+    {
+        Paths {
+            distances: HashMap::from([(start, C::ZERO)]),
+            parents: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    /// The distance to `node`: steps for BFS, depth in the search tree for DFS, total cost for
+    /// Dijkstra.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.distances.contains_key(node)
+    }
+
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    /// Every reached node, in the order the search visited it.
+    pub fn order(&self) -> &[N] {
+        &self.order
+    }
+
+    /// The path from the start to `node`, both ends included, or `None` if `node` wasn't reached.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.contains(node) {
+            return None;
+        }
+        Some(reconstruct(&self.parents, node.clone()))
+    }
+}
+
+fn reconstruct<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search, giving the fewest steps from `start` to everything reachable.
+pub fn bfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = paths.distances[&node];
+        for next in neighbors(&node) {
+            if !paths.distances.contains_key(&next) {
+                paths.distances.insert(next.clone(), distance + 1);
+                paths.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+        paths.order.push(node);
+    }
+    paths
+}
+
+/// Depth-first search, visiting neighbors in the order `neighbors` yields them, exactly as the
+/// recursive version would.
+pub fn dfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut visited = HashSet::new();
+    let mut stack = vec![(start, None)];
+    while let Some((node, parent)) = stack.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        if let Some(parent) = parent {
+            let depth = paths.distances[&parent] + 1;
+            paths.distances.insert(node.clone(), depth);
+            paths.parents.insert(node.clone(), parent);
+        }
+
+        let mut next: Vec<N> = neighbors(&node).into_iter().filter(|next| !visited.contains(next)).collect();
+        next.reverse();
+        stack.extend(next.into_iter().map(|next| (next, Some(node.clone()))));
+        paths.order.push(node);
+    }
+    paths
+}
+
+/// A queue entry for Dijkstra and A*, ordered so the `BinaryHeap` pops the lowest `priority`.
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: PartialOrd> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: PartialOrd> Eq for Queued<N, C> {}
+
+impl<N, C: PartialOrd> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: PartialOrd> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // NaN costs compare equal rather than poisoning the heap
+        other.priority.partial_cmp(&self.priority).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Dijkstra's algorithm, giving the cheapest cost from `start` to everything reachable.
+/// `neighbors` yields `(node, edge cost)` pairs, and costs must not be negative.
+pub fn dijkstra<N, C, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut settled = HashSet::new();
+    let mut queue = BinaryHeap::from([Queued { priority: C::ZERO, cost: C::ZERO, node: start }]);
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+        for (next, edge) in neighbors(&node) {
+            let next_cost = cost + edge;
+            if settled.contains(&next) || paths.distance(&next).is_some_and(|best| best <= next_cost) {
+                continue;
+            }
+            paths.distances.insert(next.clone(), next_cost);
+            paths.parents.insert(next.clone(), node.clone());
+            queue.push(Queued { priority: next_cost, cost: next_cost, node: next });
+        }
+        paths.order.push(node);
+    }
+    paths
+}
+
+/// A* search from `start` to the first node satisfying `is_goal`, returning its cost and path.
+/// `heuristic` must never overestimate the remaining cost, or the path may not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut best = HashMap::from([(start.clone(), C::ZERO)]);
+    let mut parents = HashMap::new();
+    let mut settled = HashSet::new();
+    let mut queue = BinaryHeap::from([Queued { priority: heuristic(&start), cost: C::ZERO, node: start }]);
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if is_goal(&node) {
+            return Some((cost, reconstruct(&parents, node)));
+        }
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+        for (next, edge) in neighbors(&node) {
+            let next_cost = cost + edge;
+            if settled.contains(&next) || best.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), node.clone());
+            queue.push(Queued { priority: next_cost + heuristic(&next), cost: next_cost, node: next });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Grid, Input, Pos};
+
+    const MAZE: &str = "\
+S.#....
+.##.##.
+...#...
+.#...#E
+";
+
+    fn maze() -> Grid<u8> {
+        Input::from_text(MAZE).byte_grid().unwrap()
+    }
+
+    fn open<'a>(grid: &'a Grid<u8>) -> impl Fn(&Pos) -> Vec<Pos> + 'a {
+        move |&pos| grid.neighbors4(pos).filter(|&next| grid[next] != b'#').collect()
+    }
+
+    #[test]
+    fn bfs_on_a_grid() {
+        let grid = maze();
+        let paths = bfs((0, 0), open(&grid));
+        let end = grid.find(|&c| c == b'E').unwrap();
+        assert_eq!(paths.distance(&end), Some(11));
+        let path = paths.path_to(&end).unwrap();
+        assert_eq!((path[0], path.len()), ((0, 0), 12));
+        assert!(path.windows(2).all(|step| grid.neighbors4(step[0]).any(|pos| pos == step[1])));
+        assert_eq!(paths.distance(&(0, 2)), None);
+        assert_eq!(paths.path_to(&(0, 2)), None);
+    }
+
+    #[test]
+    fn dfs_on_an_adjacency_map() {
+        let graph = HashMap::from([
+            ("a", vec!["b", "c"]),
+            ("b", vec!["d"]),
+            ("c", vec!["d", "e"]),
+            ("d", vec![]),
+            ("e", vec!["a"]),
+        ]);
+        let paths = dfs("a", |node| graph[node].clone());
+        assert_eq!(paths.order(), ["a", "b", "d", "c", "e"]);
+        assert_eq!(paths.path_to(&"e").unwrap(), ["a", "c", "e"]);
+        assert_eq!(paths.distance(&"d"), Some(2));
+    }
+
+    #[test]
+    fn dfs_does_not_overflow_on_long_chains() {
+        let paths = dfs(0u32, |&n| (n < 100_000).then_some(n + 1));
+        assert_eq!(paths.distance(&100_000), Some(100_000));
+    }
+
+    #[test]
+    fn dijkstra_with_integer_and_float_costs() {
+        let graph: HashMap<char, Vec<(char, u32)>> = HashMap::from([
+            ('a', vec![('b', 7), ('c', 2)]),
+            ('b', vec![('d', 1)]),
+            ('c', vec![('b', 3), ('d', 8)]),
+            ('d', vec![]),
+        ]);
+        let paths = dijkstra('a', |node| graph[node].clone());
+        assert_eq!(paths.distance(&'d'), Some(6));
+        assert_eq!(paths.path_to(&'d').unwrap(), ['a', 'c', 'b', 'd']);
+
+        let halves = dijkstra(0u8, |&n| if n < 4 { vec![(n + 1, 0.5), (n + 2, 1.5)] } else { vec![] });
+        assert_eq!(halves.distance(&4), Some(2.0));
+    }
+
+    #[test]
+    fn astar_matches_bfs() {
+        let grid = maze();
+        let end = grid.find(|&c| c == b'E').unwrap();
+        let manhattan = |&(row, col): &Pos| row.abs_diff(end.0) + col.abs_diff(end.1);
+        let steps = |pos: &Pos| open(&grid)(pos).into_iter().map(|next| (next, 1));
+        let (cost, path) = astar((0, 0), steps, manhattan, |&pos| pos == end).unwrap();
+        assert_eq!((cost, path.len()), (11, 12));
+        assert!(astar((0, 0), steps, |_| 0, |&pos| pos == (0, 2)).is_none());
+    }
+}
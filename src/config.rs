@@ -0,0 +1,45 @@
+//! Per-checkout settings read from `aoc.toml`, so everyone can keep inputs wherever they like.
+//!
+//! ```toml
+//! input_dir = "../aoc-inputs/2023"
+//! ```
+//!
+//! The `--input-dir` flag and the `AOC_INPUT_DIR` environment variable both take precedence.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use crate::error::{Error, Result};
+
+pub const DEFAULT_CONFIG_PATH: &str = "aoc.toml";
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub input_dir: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config> {
+        let name = path.display().to_string();
+        let text = fs::read_to_string(path).map_err(|source| Error::Io {
+            name: name.clone(),
+            source,
+        })?;
+        toml::from_str(&text).map_err(|err| Error::Config(format!("{}: {}", name, err)))
+    }
+
+    /// Like `load`, but a missing file just means the defaults.
+    pub fn load_optional(path: &Path) -> Result<Config> {
+        match Config::load(path) {
+            Err(Error::Io { source, .. }) if source.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            other => other,
+        }
+    }
+
+    pub fn input_dir(&self) -> &Path {
+        self.input_dir.as_deref().unwrap_or(Path::new(DEFAULT_INPUT_DIR))
+    }
+}
@@ -1,21 +1,30 @@
 use std::collections::HashMap;
-use std::path::Path;
 use regex::Regex;
-use crate::common::{ get_trimmed_lines };
+use crate::common::{Input, Line};
+use crate::error::Result;
+use crate::solution::{Answer, Puzzle};
 
-pub fn part1(file_path: &Path) -> String {
-    let values: Vec<u64> = get_trimmed_lines(file_path).iter().map(|line| {
-        let first = line.chars().find(|c| c.is_digit(10)).unwrap();
-        let last = line.chars().rev().find(|c| c.is_digit(10)).unwrap();
-        format!("{first}{last}").parse::<u64>().unwrap()
-    }).collect();
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&input.trimmed_lines())
+}
+
+pub fn solve_part1(lines: &[Line]) -> Result<Answer> {
+    let values: Vec<u64> = lines.iter().map(|line| {
+        let first = line.chars().find(|c| c.is_ascii_digit()).ok_or_else(|| line.error("line has no digits"))?;
+        let last = line.chars().rev().find(|c| c.is_ascii_digit()).ok_or_else(|| line.error("line has no digits"))?;
+        Ok(format!("{first}{last}").parse::<u64>().unwrap())
+    }).collect::<Result<_>>()?;
 
     let sum: u64 = values.iter().sum();
-    format!("{}", sum)
+    Ok(sum.into())
 }
 
 
-pub fn part2(file_path: &Path) -> String {
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&input.trimmed_lines())
+}
+
+pub fn solve_part2(lines: &[Line]) -> Result<Answer> {
     let number_words = [
         ("one", 1u8),
         ("two", 2u8),
@@ -36,29 +45,87 @@ pub fn part2(file_path: &Path) -> String {
         let reversed: String = word.chars().rev().collect();
         pattern_map.insert(reversed, *n);
     }
-    let mut pattern_keys: Vec<&str> = pattern_map.keys().into_iter().map(|s| s.as_str()).collect();
+    let pattern_keys: Vec<&str> = pattern_map.keys().map(|s| s.as_str()).collect();
 
     let regex = Regex::new(pattern_keys.join("|").as_str()).unwrap();
 
-    let values: Vec<u64> = get_trimmed_lines(file_path).iter().map(|line| {
+    let values: Vec<u64> = lines.iter().map(|line| {
         // find returns byte index, not char index, but that's fine for this purpose
         let first = regex.find_iter(line).map(|_match| {
             let pos = _match.start();
             let count = pattern_map.get(_match.as_str()).unwrap();
             (pos, count)
-        }).min_by_key(|tup| tup.0).unwrap().1;
+        }).min_by_key(|tup| tup.0).ok_or_else(|| line.error("line has no digits or number words"))?.1;
 
-        let line_reversed: String = line.chars().into_iter().rev().collect();
+        let line_reversed: String = line.chars().rev().collect();
         let last = regex.find_iter(line_reversed.as_str()).map(|_match| {
             let pos = _match.start();
             let count = pattern_map.get(_match.as_str()).unwrap();
             (pos, count)
-        }).min_by_key(|tup| tup.0).unwrap().1;
+        }).min_by_key(|tup| tup.0).ok_or_else(|| line.error("line has no digits or number words"))?.1;
         let ret = format!("{first}{last}").parse::<u64>().unwrap();
-        println!("ret: {}", ret);
-        ret
-    }).collect();
+        log::trace!("ret: {}", ret);
+        Ok(ret)
+    }).collect::<Result<_>>()?;
 
     let sum: u64 = values.iter().sum();
-    format!("{}", sum)
-}
\ No newline at end of file
+    Ok(sum.into())
+}
+
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    const DAY: u16 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    type Part1Input = Vec<Line>;
+    type Part2Input = Vec<Line>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        Ok(input.trimmed_lines())
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        Ok(input.trimmed_lines())
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+
+    const EXAMPLE_PART2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(142));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE_PART2)).unwrap(), Answer::from(281));
+    }
+}
@@ -1,185 +1,191 @@
-use std::collections::{HashMap, VecDeque};
-use std::path::Path;
-use array2d::Array2D;
-use crate::common::get_trimmed_lines;
-
-fn parse_input(file_path: &Path) -> Array2D<u8> {
-    let lines = get_trimmed_lines(file_path);
-    let byterows: Vec<Vec<u8>> = lines.iter().map(|x| {
-        x.bytes().collect()
-    }).collect();
-
-    Array2D::from_rows(byterows.as_slice()).unwrap()
-}
-
-fn _find_loop(board: &Array2D<u8>, start: (usize, usize), path: &mut Vec<(usize, usize)>) -> bool {
-    // println!("_find_loop {:?}", path);
-    let current = *path.last().unwrap_or(&start);
+use crate::common::{search, Grid, Input, Pos};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Puzzle};
 
-    if path.len() > 2 && start == current {
-        return true;
-    }
+pub fn parse_input(input: &Input) -> Result<Grid<u8>> {
+    Ok(input.byte_grid()?)
+}
 
-    let to_left = (current.0 as i64, current.1 as i64 + 1);
-    let to_right = (current.0 as i64, current.1 as i64 - 1);
-    let to_up = (current.0 as i64 - 1, current.1 as i64);
-    let to_down = (current.0 as i64 + 1, current.1 as i64);
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
 
-    for next_move in [to_left, to_right, to_up, to_down] {
-        if !is_valid_move(&board, current, next_move) {
-            continue;
-        }
-        let next_move_usize = (next_move.0 as usize, next_move.1 as usize);
+impl Direction {
+    const ALL: [Direction; 4] = [Direction::Right, Direction::Left, Direction::Up, Direction::Down];
 
-        if path.contains(&next_move_usize) {
-            continue;
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
         }
+    }
 
-        path.push(next_move_usize);
-
-        if _find_loop(board, start, path) {
-            return true;
+    fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
         }
-        path.pop();
     }
-
-    return false;
 }
 
-fn is_valid_move(board: &Array2D<u8>, current: (usize, usize), next: (i64, i64)) -> bool {
-    if next.0 < 0 || next.0 >= (board.num_rows() as i64) || next.1 < 0 || next.1 >= (board.num_columns() as i64) {
-        return false;
+/// Whether the pipe on `tile` has an opening facing `direction`.
+fn connects(tile: u8, direction: Direction) -> bool {
+    match tile {
+        b'|' => direction == Direction::Up || direction == Direction::Down,
+        b'-' => direction == Direction::Left || direction == Direction::Right,
+        b'L' => direction == Direction::Up || direction == Direction::Right,
+        b'J' => direction == Direction::Up || direction == Direction::Left,
+        b'7' => direction == Direction::Down || direction == Direction::Left,
+        b'F' => direction == Direction::Down || direction == Direction::Right,
+        b'S' => true,
+        _ => false,
     }
+}
 
-    #[derive(Eq, PartialEq)]
-    enum Direction {
-        Up,
-        Down,
-        Left,
-        Right,
-    }
+/// The tile reached by leaving `current` towards `direction`, if the pipes on both sides join up.
+fn next_move(board: &Grid<u8>, current: Pos, direction: Direction) -> Option<Pos> {
+    let next = board.offset(current, direction.delta())?;
+    (connects(board[current], direction) && connects(board[next], direction.opposite())).then_some(next)
+}
 
-    let next_usize = (next.0 as usize, next.1 as usize);
-    let direction =
-        if next_usize.0 == current.0 {
-            if next_usize.1 == current.1 + 1 {
-                Direction::Right
-            } else if next_usize.1 == current.1 - 1 {
-                Direction::Left
-            } else {
-                return false;
-            }
-        } else if next_usize.1 == current.1 {
-            if next_usize.0 == current.0 + 1 {
-                Direction::Down
-            } else if next_usize.0 == current.0 - 1 {
-                Direction::Up
+/// The tiles reachable in one step from `current`.
+fn moves(board: &Grid<u8>, current: Pos) -> impl Iterator<Item = Pos> + '_ {
+    Direction::ALL.into_iter().filter_map(move |direction| next_move(board, current, direction))
+}
+
+/// Finds the loop through `start_pos`, returned as `start_pos`, the tiles around the loop, and
+/// `start_pos` again.
+pub fn find_loop(board: &Grid<u8>, start_pos: Pos) -> Result<Vec<Pos>> {
+    // leave the start one way and search for a route back that doesn't reuse that first step
+    for first in moves(board, start_pos) {
+        let paths = search::bfs(first, |&pos| {
+            if pos == start_pos {
+                Vec::new()
             } else {
-                return false;
-            }
-        } else {
-            return false;
-        };
-
-    match board[next_usize] {
-        b'|' => {
-            if direction == Direction::Left || direction == Direction::Right {
-                return false;
+                moves(board, pos).filter(|&next| pos != first || next != start_pos).collect()
             }
+        });
+
+        if let Some(loop_path) = paths.path_to(&start_pos) {
+            let mut ret = Vec::new();
+            ret.push(start_pos);
+            ret.extend(loop_path);
+            log::trace!("loop is {:?}", ret);
+            return Ok(ret);
         }
-        b'-' => {
-            if direction == Direction::Up || direction == Direction::Down {
-                return false;
-            }
-        },
-        b'L' => {
-            if direction == Direction::Up || direction == Direction::Right {
-                return false;
-            }
-        },
-        b'J' => {
-            if direction == Direction::Up || direction == Direction::Left {
-                return false;
+    }
 
-            }
-        },
-        b'7' => {
-            if direction == Direction::Down || direction == Direction::Left {
-                return false;
+    Err(Error::Solve("unable to find loop".to_string()))
+}
 
-            }
-        },
-        b'F' => {
-            if direction == Direction::Down || direction == Direction::Right {
-                return false;
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input(input)?)
+}
 
-            }
-        },
-        b'S' => {
+pub fn solve_part1(board: &Grid<u8>) -> Result<Answer> {
+    let start_pos = board.find(|tile| *tile == b'S').ok_or_else(|| Error::Solve("no `S` start tile".to_string()))?;
 
-        },
-        _ => {
+    let loop_path = find_loop(board, start_pos)?;
 
-        }
-    };
-
-    match board[current] {
-        b'|' => {
-            direction == Direction::Up || direction == Direction::Down
-        },
-        b'-' => {
-            direction == Direction::Left || direction == Direction::Right
-        },
-        b'L' => {
-            direction == Direction::Up || direction == Direction::Right
-        },
-        b'J' => {
-            direction == Direction::Up || direction == Direction::Left
-        },
-        b'7' => {
-            direction == Direction::Down || direction == Direction::Left
-        },
-        b'F' => {
-            direction == Direction::Down || direction == Direction::Right
-        },
-        b'S' => {
-            true
-        },
-        _ => {
-            false
-        }
-    }
+    Ok((loop_path.len() / 2).into())
 }
 
-fn find_loop(board: &Array2D<u8>, start_pos: (usize, usize)) -> Vec<(usize, usize)> {
-    let mut loop_path = Vec::new();
-    if !_find_loop(board, start_pos, &mut loop_path) {
-        panic!("unable to find loop");
-    }
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input(input)?)
+}
 
-    let mut ret = Vec::new();
-    ret.push(start_pos);
-    ret.extend(loop_path);
-    println!("loop is {:?}", ret);
-    ret
+pub fn solve_part2(_board: &Grid<u8>) -> Result<Answer> {
+    Ok(Answer::Unsolved)
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let board = parse_input(file_path);
+pub struct Day10;
 
-    let start_pos = board.enumerate_row_major().find_map(|(pos, item)| {
-        if *item == b'S' {
-            Some(pos)
-        } else {
-            None
-        }
-    }).unwrap();
+impl Puzzle for Day10 {
+    const DAY: u16 = 10;
+    const TITLE: &'static str = "Pipe Maze";
+
+    const PARTS: &'static [u16] = &[1];
 
-    let loop_path = find_loop(&board, start_pos);
+    type Part1Input = Grid<u8>;
+    type Part2Input = Grid<u8>;
 
-    (loop_path.len() / 2).to_string()
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
 }
 
-pub fn part2(file_path: &Path) -> String {
-    "".to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF
+";
+
+    const EXAMPLE_COMPLEX: &str = "\
+7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ
+";
+
+    const EXAMPLE_PART2: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(4));
+    }
+
+    #[test]
+    fn part1_example_complex() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE_COMPLEX)).unwrap(), Answer::from(8));
+    }
+
+    #[test]
+    #[ignore = "part 2 is not implemented yet"]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE_PART2)).unwrap(), Answer::from(4));
+    }
+
+    #[test]
+    fn large_loop_does_not_overflow() {
+        // the old recursive search overflowed the stack from around 100x100
+        let text = crate::generate::generate(10, 1, 200).unwrap();
+        assert_eq!(part1(&Input::from_text(text)).unwrap(), Answer::from(200 * 200 / 2));
+    }
 }
@@ -1,63 +1,65 @@
-use crate::common::{ get_trimmed_lines };
-use std::path::Path;
-
-struct Draw {
-    blue: u64,
-    red: u64,
-    green: u64,
+use crate::common::{parse, Input};
+use crate::error::{ParseResult, Result};
+use crate::solution::{Answer, Puzzle};
+
+#[derive(Clone, Debug)]
+pub struct Draw {
+    pub blue: u64,
+    pub red: u64,
+    pub green: u64,
 }
 
-struct Game {
-    gameid: u64,
-    draws: Vec<Draw>
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub gameid: u64,
+    pub draws: Vec<Draw>
 }
 
-fn parse_games(file_path: &Path) -> Vec<Game> {
-    get_trimmed_lines(file_path).iter().map(|line| {
-        let parts: Vec<&str> = line.split(": ").collect();
-        let header: Vec<&str> = parts[0].split(" ").collect();
-        let gameid = header[1].parse::<u64>().unwrap();
+pub fn parse_games(input: &Input) -> Result<Vec<Game>> {
+    input.trimmed_lines().iter().map(|line| {
+        let (header, draws_text) = parse::split_pair(line, line, ": ", "expected `Game N: ...`")?;
+        let gameid = parse::numbered(line, header, "Game")?;
 
-        let draws = parts[1].split("; ").into_iter().map(|drawline| {
-            let cube_words: Vec<&str> = drawline.split(", ").collect();
+        let draws = parse::delimited(draws_text, ";").map(|drawline| {
             let mut blue = 0;
             let mut green = 0;
             let mut red = 0;
 
-            for word in cube_words.iter() {
-                let pieces: Vec<&str> = word.split(" ").collect();
-                match pieces[1] {
+            for word in parse::delimited(drawline, ",") {
+                let (count, color) = parse::split_pair(line, word, " ", "expected `<count> <color>`")?;
+                match color {
                     "blue" => {
-                        blue = pieces[0].parse().unwrap()
+                        blue = parse::number(line, count)?
                     },
                     "green" => {
-                        green = pieces[0].parse().unwrap()
+                        green = parse::number(line, count)?
                     },
                     "red" => {
-                        red = pieces[0].parse().unwrap()
+                        red = parse::number(line, count)?
                     },
-                    _ => panic!("unknown color")
+                    _ => return Err(line.error_at(color, "unknown color"))
                 };
             }
 
-            Draw {
-                blue: blue,
-                green: green,
-                red: red,
-            }
-        }).collect();
+            Ok(Draw {
+                blue,
+                green,
+                red,
+            })
+        }).collect::<ParseResult<_>>()?;
 
-        Game {
-            gameid: gameid,
-            draws: draws
-        }
+        Ok(Game {
+            gameid,
+            draws
+        })
     }).collect()
 }
 
-pub fn part1(file_path: &Path) -> String {
-
-    let games = parse_games(file_path);
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_games(input)?)
+}
 
+pub fn solve_part1(games: &[Game]) -> Result<Answer> {
     let maxred = 12;
     let maxgreen = 13;
     let maxblue = 14;
@@ -68,12 +70,14 @@ pub fn part1(file_path: &Path) -> String {
         })
     });
 
-    validgames.map(|game| game.gameid).sum::<u64>().to_string()
+    Ok(validgames.map(|game| game.gameid).sum::<u64>().into())
 }
 
-pub fn part2(file_path: &Path) -> String {
-    let games = parse_games(file_path);
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_games(input)?)
+}
 
+pub fn solve_part2(games: &[Game]) -> Result<Answer> {
     let powers: Vec<u64> = games.iter().map(|game| {
         let emptydraw = Draw {
             red: 0,
@@ -91,6 +95,54 @@ pub fn part2(file_path: &Path) -> String {
     }).collect();
     let sum = powers.iter().sum::<u64>();
 
-    sum.to_string()
+    Ok(sum.into())
 }
 
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    const DAY: u16 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    type Part1Input = Vec<Game>;
+    type Part2Input = Vec<Game>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_games(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_games(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(8));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(2286));
+    }
+}
@@ -1,160 +1,93 @@
-use std::collections::HashMap;
-use std::fs::read;
-use std::path::Path;
-use crate::common::{ get_trimmed_lines };
-use array2d::Array2D;
+use std::collections::{BTreeSet, HashMap};
+use crate::common::{Grid, Input, Pos};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Puzzle};
 
 #[derive(Copy, Clone, Debug)]
-struct NumberLocation {
-    number: u32,
-    length: usize,
-    row: usize,
-    col: usize,
+pub struct NumberLocation {
+    pub number: u32,
+    pub length: usize,
+    pub row: usize,
+    pub col: usize,
 }
 
-fn read_numbered_locations(board: &Array2D<char>) -> Vec<NumberLocation> {
+pub fn read_numbered_locations(board: &Grid<char>) -> Result<Vec<NumberLocation>> {
     let mut number_locations: Vec<NumberLocation> = Vec::new();
-    let mut current: Option<NumberLocation> = None;
-
-    for ((row, col), item) in board.enumerate_row_major() {
-        match item.to_digit(10) {
-            Some(digit) => {
-                match &mut current {
-                    None => {
-                        current = Some(NumberLocation {
-                            row: row,
-                            col: col,
-                            number: digit,
-                            length: 1,
-                        });
-                    },
-                    Some(_current) => {
-                        _current.number = (&_current.number * 10) + digit;
-                        _current.length += 1;
-                    }
-                }
-            },
-            None => {
-                match current {
-                    None => {
-
-                    },
-                    Some(_current) => {
-                        number_locations.push(_current);
-                        current = None
+
+    for (row, cells) in board.rows().enumerate() {
+        // numbers never wrap onto the next row
+        let mut current: Option<NumberLocation> = None;
+        for (col, item) in cells.iter().enumerate() {
+            match item.to_digit(10) {
+                Some(digit) => {
+                    match &mut current {
+                        None => {
+                            current = Some(NumberLocation {
+                                row,
+                                col,
+                                number: digit,
+                                length: 1,
+                            });
+                        },
+                        Some(_current) => {
+                            _current.number = _current.number.checked_mul(10).and_then(|n| n.checked_add(digit)).ok_or_else(|| {
+                                Error::Solve(format!("number at row {}, column {} is too large", _current.row + 1, _current.col + 1))
+                            })?;
+                            _current.length += 1;
+                        }
                     }
+                },
+                None => {
+                    number_locations.extend(current.take());
                 }
             }
         }
+        number_locations.extend(current);
     }
-    number_locations
+    Ok(number_locations)
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let lines = get_trimmed_lines(file_path);
-    let bytes: Vec<Vec<char>> = lines.iter().map(|line| {
-        line.chars().collect()
-    }).collect();
-
-    let board = Array2D::from_rows(&bytes).unwrap();
-
-    let numbered_locations = read_numbered_locations(&board);
+/// Every cell touching the number, diagonals included, each listed once.
+fn adjacent_cells(board: &Grid<char>, loc: &NumberLocation) -> BTreeSet<Pos> {
+    let span = loc.col..loc.col + loc.length;
+    (loc.col..loc.col + loc.length)
+        .flat_map(|col| board.neighbors8((loc.row, col)))
+        .filter(|&(row, col)| row != loc.row || !span.contains(&col))
+        .collect()
+}
 
-    let filtered = numbered_locations.into_iter().filter(|loc| {
-        let locrow = loc.row as i32;
-        let loccol = loc.col as i32;
-        let loclength = loc.length as i32;
-        let mut coords_to_check = Vec::new();
-        coords_to_check.push((locrow - 1, loccol - 1));
-        coords_to_check.push((locrow, loccol - 1));
-        coords_to_check.push((locrow + 1, loccol - 1));
-        coords_to_check.push((locrow - 1, loccol + loclength));
-        coords_to_check.push((locrow, loccol + loclength));
-        coords_to_check.push((locrow + 1, loccol + loclength));
-        for col in loccol..loccol+loclength {
-            coords_to_check.push((locrow - 1, col));
-            coords_to_check.push((locrow + 1, col));
-        }
+pub fn parse_board(input: &Input) -> Result<Grid<char>> {
+    Ok(input.char_grid()?)
+}
 
-        let filtered_coords = coords_to_check.into_iter().filter_map(|coord| {
-            if coord.0 < 0 || coord.1 < 0 {
-                None
-            } else {
-                Some((coord.0 as usize, coord.1 as usize))
-            }
-        });
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_board(input)?)
+}
 
-        for (_row, _col) in filtered_coords {
-            let c = board.get(_row, _col);
-            match c {
-                Some('.') => {
-                },
-                Some('0'..='9') => {
-                },
-                None => {
-                },
-                Some(_) => {
-                    return true;
-                }
-            }
-        }
+pub fn solve_part1(board: &Grid<char>) -> Result<Answer> {
+    let numbered_locations = read_numbered_locations(board)?;
 
-        false
+    let filtered = numbered_locations.into_iter().filter(|loc| {
+        adjacent_cells(board, loc).into_iter().any(|pos| !matches!(board[pos], '.' | '0'..='9'))
     });
 
-    filtered.map(|loc| {
-        println!("loc: {:?}", loc);
-        loc.number
-    }).sum::<u32>().to_string()
+    Ok(filtered.map(|loc| {
+        log::trace!("loc: {:?}", loc);
+        loc.number as u64
+    }).sum::<u64>().into())
 }
 
-pub fn part2(file_path: &Path) -> String {
-    let lines = get_trimmed_lines(file_path);
-    let bytes: Vec<Vec<char>> = lines.iter().map(|line| {
-        line.chars().collect()
-    }).collect();
-
-    let board = Array2D::from_rows(&bytes).unwrap();
-
-    let numbered_locations = read_numbered_locations(&board);
-
-    let nearby_gears = numbered_locations.into_iter().enumerate().filter_map(|(idx, loc)| {
-        let locrow = loc.row as i32;
-        let loccol = loc.col as i32;
-        let loclength = loc.length as i32;
-        let mut coords_to_check = Vec::new();
-        coords_to_check.push((locrow - 1, loccol - 1));
-        coords_to_check.push((locrow, loccol - 1));
-        coords_to_check.push((locrow + 1, loccol - 1));
-        coords_to_check.push((locrow - 1, loccol + loclength));
-        coords_to_check.push((locrow, loccol + loclength));
-        coords_to_check.push((locrow + 1, loccol + loclength));
-        for col in loccol..loccol+loclength {
-            coords_to_check.push((locrow - 1, col));
-            coords_to_check.push((locrow + 1, col));
-        }
-
-        let filtered_coords = coords_to_check.into_iter().filter_map(|coord| {
-            if coord.0 < 0 || coord.1 < 0 {
-                None
-            } else {
-                Some((coord.0 as usize, coord.1 as usize))
-            }
-        });
-
-        for (_row, _col) in filtered_coords {
-            let c = board.get(_row, _col);
-            match c {
-                Some('*') => {
-                    return Some((loc, (_row, _col)));
-                },
-                _ => {
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_board(input)?)
+}
 
-                }
-            }
-        }
+pub fn solve_part2(board: &Grid<char>) -> Result<Answer> {
+    let numbered_locations = read_numbered_locations(board)?;
 
-        None
+    let nearby_gears = numbered_locations.into_iter().flat_map(|loc| {
+        adjacent_cells(board, &loc).into_iter()
+            .filter(|&pos| board[pos] == '*')
+            .map(move |gear_coord| (loc, gear_coord))
     });
 
     let mut gear_map = HashMap::new();
@@ -163,13 +96,92 @@ pub fn part2(file_path: &Path) -> String {
         locs.push(loc);
     }
 
-    let gear_ratios = gear_map.iter().filter_map(|(gear_coord, locs)| {
+    let gear_ratios = gear_map.values().filter_map(|locs| {
         if locs.len() == 2 {
-            Some(locs[0].number * locs[1].number)
+            Some(locs[0].number as u64 * locs[1].number as u64)
         } else {
             None
         }
     });
 
-    gear_ratios.sum::<u32>().to_string()
-}
\ No newline at end of file
+    Ok(gear_ratios.sum::<u64>().into())
+}
+
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    const DAY: u16 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+
+    type Part1Input = Grid<char>;
+    type Part2Input = Grid<char>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_board(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_board(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(4361));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(467835));
+    }
+
+    #[test]
+    fn number_too_large_is_an_error() {
+        // found by fuzz/fuzz_targets/parse_day3.rs, used to overflow
+        let board = parse_board(&Input::from_text("99999999999*")).unwrap();
+        assert!(read_numbered_locations(&board).is_err());
+    }
+
+    #[test]
+    fn numbers_end_at_the_row_edge() {
+        let board = parse_board(&Input::from_text("..12\n34..\n")).unwrap();
+        let numbers: Vec<u32> = read_numbered_locations(&board).unwrap().iter().map(|loc| loc.number).collect();
+        assert_eq!(numbers, [12, 34]);
+    }
+
+    #[test]
+    fn number_at_the_end_of_the_board_counts() {
+        assert_eq!(part1(&Input::from_text("...\n.*.\n..7\n")).unwrap(), Answer::from(7));
+    }
+
+    #[test]
+    fn number_next_to_two_gears_counts_for_both() {
+        // 5 touches both stars, so each star is a gear: 2*5 + 5*3
+        assert_eq!(part2(&Input::from_text("2*5*3\n")).unwrap(), Answer::from(25));
+    }
+}
@@ -1,49 +1,41 @@
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::iter::Map;
-use std::path::Path;
-use std::vec::IntoIter;
 
-use crate::common::{ get_trimmed_lines};
+use crate::common::{parse, Input};
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Puzzle};
 
 #[derive(Clone, Debug)]
-struct Card {
-    card_number: u64,
-    winning_numbers: Vec<u64>,
-    your_numbers: Vec<u64>,
+pub struct Card {
+    pub card_number: u64,
+    pub winning_numbers: Vec<u64>,
+    pub your_numbers: Vec<u64>,
 }
 
-fn calc_winning_card_count(card: &Card) -> usize {
-    let winning_set: HashSet<u64> = HashSet::from_iter(card.winning_numbers.iter().map(|n| *n));
+pub fn calc_winning_card_count(card: &Card) -> usize {
+    let winning_set: HashSet<u64> = HashSet::from_iter(card.winning_numbers.iter().copied());
     card.your_numbers.iter().filter(|n| winning_set.contains(n)).count()
 }
 
-fn parse_cards(lines: Vec<String>) -> Vec<Card> {
-    lines.into_iter().map(|line| {
-        let pieces: Vec<&str> = line.split(": ").collect();
-        let card_pieces: Vec<&str> = pieces[0].split_whitespace().collect();
-        let card_number = card_pieces[1].parse::<u64>().unwrap();
-
-        let number_pieces: Vec<Vec<u64>> = pieces[1].split("|").map(|part| {
-            part.split_whitespace().map(|n| {
-                n.trim().parse::<u64>().unwrap()
-            }).collect()
-        }).collect();
-
-        Card {
-            card_number: card_number,
-            winning_numbers: number_pieces[0].clone(),
-            your_numbers: number_pieces[1].clone(),
-        }
+pub fn parse_cards(input: &Input) -> Result<Vec<Card>> {
+    input.trimmed_lines().iter().map(|line| {
+        let (header, numbers) = parse::split_pair(line, line, ": ", "expected `Card N: ...`")?;
+        let (winning, yours) = parse::split_pair(line, numbers, "|", "expected `|` between the number lists")?;
+
+        Ok(Card {
+            card_number: parse::numbered(line, header, "Card")?,
+            winning_numbers: parse::numbers(line, winning)?,
+            your_numbers: parse::numbers(line, yours)?,
+        })
     }).collect()
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let lines = get_trimmed_lines(file_path);
-
-    let cards = parse_cards(lines);
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_cards(input)?)
+}
 
-    let count = cards.into_iter().map(|card| {
-        let winning_num_count = calc_winning_card_count(&card);
+pub fn solve_part1(cards: &[Card]) -> Result<Answer> {
+    let count = cards.iter().map(|card| {
+        let winning_num_count = calc_winning_card_count(card);
         if winning_num_count == 0 {
             0
         } else {
@@ -52,15 +44,15 @@ pub fn part1(file_path: &Path) -> String {
     });
 
     let s = count.sum::<u32>();
-    s.to_string()
+    Ok(s.into())
 }
 
 
-pub fn part2(file_path: &Path) -> String {
-    let lines = get_trimmed_lines(file_path);
-
-    let original_cards: Vec<Card> = parse_cards(lines);
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_cards(input)?)
+}
 
+pub fn solve_part2(original_cards: &[Card]) -> Result<Answer> {
     let card_lookup: HashMap<u64, usize> = HashMap::from_iter(original_cards.iter().map(|card| {
         (card.card_number, calc_winning_card_count(card))
     }));
@@ -74,12 +66,64 @@ pub fn part2(file_path: &Path) -> String {
         }
 
         let card_number = current_cards.pop_front().unwrap();
-        let card_count = *card_lookup.get(&card_number).unwrap();
+        let card_count = *card_lookup.get(&card_number).ok_or_else(|| {
+            Error::Solve(format!("won a copy of card {}, which isn't in the input", card_number))
+        })?;
         for n in 0..card_count {
             current_cards.push_back(card_number + (n as u64) + 1);
             total_count += 1;
         }
     }
 
-    total_count.to_string()
-}
\ No newline at end of file
+    Ok(total_count.into())
+}
+
+pub struct Day4;
+
+impl Puzzle for Day4 {
+    const DAY: u16 = 4;
+    const TITLE: &'static str = "Scratchcards";
+
+    type Part1Input = Vec<Card>;
+    type Part2Input = Vec<Card>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_cards(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_cards(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(13));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(30));
+    }
+}
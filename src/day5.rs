@@ -1,18 +1,19 @@
 use std::collections::HashMap;
-use std::path::Path;
-use crate::common::get_trimmed_lines;
-use crate::day5::Element::Fertilizer;
+use crate::common::interval::IntervalSet;
+use crate::common::{parse, Input, Line};
+use crate::error::{Error, ParseResult, Result};
+use crate::solution::{Answer, Puzzle, Variant};
 
 #[derive(Debug, Copy, Clone)]
-struct Range {
-    source_start: u64,
-    dest_start: u64,
-    len: usize,
+pub struct Range {
+    pub source_start: u64,
+    pub dest_start: u64,
+    pub len: usize,
 }
 
 
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
-enum Element {
+pub enum Element {
     Seed,
     Fertilizer,
     Water,
@@ -22,113 +23,281 @@ enum Element {
     Location,
     Soil,
 }
-type MapType = (Element, Element);
+pub type MapType = (Element, Element);
 
 #[derive(Debug)]
-struct SeedInfo {
-    seeds: Vec<u64>,
-    maps: HashMap<MapType, Vec<Range>>,
+pub struct SeedInfo {
+    pub seeds: Vec<u64>,
+    pub maps: HashMap<MapType, Vec<Range>>,
 }
 
 
-fn parse_input(file_path: &Path) -> SeedInfo {
-    let lines = get_trimmed_lines(file_path);
-    let mut seeds: Option<Vec<u64>> = None;
-    let mut current_map_type: Option<MapType> = None;
-    let mut current_ranges: Vec<Range> = Vec::new();
-    let mut maps: HashMap<MapType, Vec<Range>> = HashMap::new();
+fn parse_element(line: &Line, s: &str) -> ParseResult<Element> {
+    match s {
+        "seed" => Ok(Element::Seed),
+        "fertilizer" => Ok(Element::Fertilizer),
+        "water" => Ok(Element::Water),
+        "light" => Ok(Element::Light),
+        "temperature" => Ok(Element::Temperature),
+        "humidity" => Ok(Element::Humidity),
+        "location" => Ok(Element::Location),
+        "soil" => Ok(Element::Soil),
+        _ => Err(line.error_at(s, "unknown element")),
+    }
+}
 
-    for line in lines {
-        if line.starts_with("seeds:") {
-            seeds = Some(line.split(" ").skip(1).map(|s| s.parse().unwrap()).collect());
-        }
-        else if line.ends_with(" map:") {
-            match current_map_type {
-                Some(_type) => {
-                    maps.insert(current_map_type.unwrap(), current_ranges.clone());
-                },
-                None => {}
-            }
+fn parse_seeds(section: &[Line]) -> Result<Vec<u64>> {
+    let line = &section[0];
+    if let Some(extra) = section.get(1) {
+        return Err(extra.error("expected a blank line after the seeds").into());
+    }
+    Ok(parse::labeled_numbers(line, line, "seeds:")?)
+}
+
+fn parse_map(section: &[Line]) -> Result<(MapType, Vec<Range>)> {
+    let header = &section[0];
+    let typename = header.strip_suffix(" map:")
+        .ok_or_else(|| header.error("expected a `<source>-to-<destination> map:` header"))?;
+    let map_type = match typename.split('-').collect::<Vec<&str>>()[..] {
+        [source, "to", dest] => (parse_element(header, source)?, parse_element(header, dest)?),
+        _ => return Err(header.error_at(typename, "expected `<source>-to-<destination>`").into()),
+    };
 
-            let typename = line.split(" ").next().unwrap();
-            let pieces: Vec<Element> = typename.split("-").filter(|s| s != &"to").map(|s| {
-                match s {
-                    "seed" => Element::Seed,
-                    "fertilizer" => Element::Fertilizer,
-                    "water" => Element::Water,
-                    "light" => Element::Light,
-                    "temperature" => Element::Temperature,
-                    "humidity" => Element::Humidity,
-                    "location" => Element::Location,
-                    "soil" => Element::Soil,
-                    _ => {
-                        panic!("unknown element {}", s);
-                    }
-                }
-            }).collect();
-            current_map_type = Some((*pieces.get(0).unwrap(), *pieces.get(1).unwrap()));
-            current_ranges.clear();
+    let ranges = section[1..].iter().map(|line| {
+        match parse::numbers::<u64>(line, line)?[..] {
+            [dest_start, source_start, len] => Ok(Range {
+                dest_start,
+                source_start,
+                len: len as usize,
+            }),
+            _ => Err(line.error("expected `<destination start> <source start> <length>`")),
         }
-        else if !line.is_empty() {
-            let pieces: Vec<u64> = line.split(" ").map(|s| s.parse::<u64>().unwrap()).collect();
-            let range = Range {
-                dest_start: *pieces.get(0).unwrap(),
-                source_start: *pieces.get(1).unwrap(),
-                len: (*pieces.get(2).unwrap()) as usize,
-            };
-            current_ranges.push(range);
+    }).collect::<ParseResult<_>>()?;
+    Ok((map_type, ranges))
+}
+
+/// The almanac is a `seeds:` line followed by one blank-line-separated section per map.
+pub fn parse_input(input: &Input) -> Result<SeedInfo> {
+    let sections = input.sections();
+    let (seed_section, map_sections) = sections.split_first()
+        .ok_or_else(|| Error::Solve("input has no `seeds:` line".to_string()))?;
+
+    Ok(SeedInfo {
+        seeds: parse_seeds(seed_section)?,
+        maps: map_sections.iter().map(|section| parse_map(section)).collect::<Result<_>>()?,
+    })
+}
+
+fn next_map(element: Element, seed_info: &SeedInfo) -> Result<(Element, &Vec<Range>)> {
+    seed_info.maps.iter().find_map(|((_source_element, _dest_element), ranges)| {
+        if *_source_element == element {
+            Some((*_dest_element, ranges))
+        } else {
+            None
         }
+    }).ok_or_else(|| Error::Solve(format!("no map from {:?}", element)))
+}
 
-    }
+pub fn translate_number(source_n: u64, source_element: Element, dest_element: Element, seed_info: &SeedInfo) -> Result<u64> {
+    let mut n = source_n;
+    let mut element = source_element;
+    // each map can be followed at most once, anything longer is a cycle
+    for _ in 0..seed_info.maps.len() {
+        let (_dest_element, ranges) = next_map(element, seed_info)?;
 
-    maps.insert(current_map_type.unwrap(), current_ranges.clone());
+        for range in ranges {
+            if n >= range.source_start && n - range.source_start < range.len as u64 {
+                n = range.dest_start + (n - range.source_start);
+                break;
+            }
+        }
 
-    SeedInfo {
-        seeds: seeds.unwrap(),
-        maps: maps,
+        element = _dest_element;
+        if element == dest_element {
+            return Ok(n);
+        }
     }
+    Err(Error::Solve(format!("maps never lead from {:?} to {:?}", source_element, dest_element)))
 }
 
-fn translate_number(source_n: u64, source_element: Element, dest_element: Element, seed_info: &SeedInfo) -> u64 {
-    for ((_source_element, _dest_element), ranges) in seed_info.maps.iter() {
-        if *_source_element == source_element {
-            let mut dest_n = source_n;
-            for range in ranges {
-                if source_n >= range.source_start && source_n < range.source_start + (range.len as u64) {
-                    dest_n = range.dest_start + (source_n - range.source_start);
-                    break;
-                }
-            }
+/// Like `translate_number`, but for a whole set of seeds at once, splitting ranges wherever they
+/// straddle the edge of a map range.
+pub fn translate_ranges(source: IntervalSet<u64>, source_element: Element, dest_element: Element, seed_info: &SeedInfo) -> Result<IntervalSet<u64>> {
+    let overflow = || Error::Solve("map range runs past the largest u64".to_string());
+    let mut current = source;
+    let mut element = source_element;
+    for _ in 0..seed_info.maps.len() {
+        let (_dest_element, ranges) = next_map(element, seed_info)?;
 
-            if *_dest_element != dest_element {
-                return translate_number(dest_n, *_dest_element, dest_element, seed_info);
-            } else {
-                return dest_n;
-            }
+        let mut translated = IntervalSet::new();
+        for range in ranges {
+            let map_end = range.source_start.checked_add(range.len as u64).ok_or_else(overflow)?;
+            let map_range = IntervalSet::from(range.source_start..map_end);
+            let offset = range.dest_start as i128 - range.source_start as i128;
+
+            // a value only goes through the first range that covers it, as in `translate_number`
+            let moved = current.intersection(&map_range).shift(offset).ok_or_else(overflow)?;
+            translated = translated.union(&moved);
+            current = current.difference(&map_range);
+        }
+        // anything no range covered maps to itself
+        current = current.union(&translated);
+
+        element = _dest_element;
+        if element == dest_element {
+            return Ok(current);
         }
     }
-    panic!("unable to find source element");
+    Err(Error::Solve(format!("maps never lead from {:?} to {:?}", source_element, dest_element)))
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let info = parse_input(file_path);
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input(input)?)
+}
 
+pub fn solve_part1(info: &SeedInfo) -> Result<Answer> {
     let locations: Vec<u64> = info.seeds.iter().map(|seed| {
-        translate_number(*seed, Element::Seed, Element::Location, &info)
-    }).collect();
-    locations.iter().min().unwrap().to_string()
+        translate_number(*seed, Element::Seed, Element::Location, info)
+    }).collect::<Result<_>>()?;
+    locations.iter().min().map(|n| (*n).into()).ok_or_else(|| Error::Solve("no seeds".to_string()))
+}
+
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input(input)?)
 }
 
-pub fn part2(file_path: &Path) -> String {
-    let info = parse_input(file_path);
+pub fn solve_part2(info: &SeedInfo) -> Result<Answer> {
+    if !info.seeds.len().is_multiple_of(2) {
+        return Err(Error::Solve("seeds must come in start/length pairs".to_string()));
+    }
+
+    let seed_ranges = info.seeds.chunks(2).map(|seed_chunk| {
+        let end = seed_chunk[0].checked_add(seed_chunk[1]).ok_or_else(|| Error::Solve("seed range runs past the largest u64".to_string()))?;
+        Ok(seed_chunk[0]..end)
+    }).collect::<Result<_>>()?;
+    let locations = translate_ranges(seed_ranges, Element::Seed, Element::Location, info)?;
+    locations.min().map(|n| n.into()).ok_or_else(|| Error::Solve("no seeds".to_string()))
+}
+
+/// Translates every seed one at a time.
+pub fn solve_part2_naive(info: &SeedInfo) -> Result<Answer> {
+    if !info.seeds.len().is_multiple_of(2) {
+        return Err(Error::Solve("seeds must come in start/length pairs".to_string()));
+    }
 
-    let locations: Vec<u64> = info.seeds.chunks(2).map(|seed_chunk| {
+    let locations: Vec<u64> = info.seeds.chunks(2).flat_map(|seed_chunk| {
         let seed_chunk_from = seed_chunk[0];
         let seed_chunk_len = seed_chunk[1];
-        println!("seed {:?}", seed_chunk);
+        log::debug!("seed {:?}", seed_chunk);
         (seed_chunk_from..(seed_chunk_from+seed_chunk_len)).map(|seed| {
-            translate_number(seed, Element::Seed, Element::Location, &info)
+            translate_number(seed, Element::Seed, Element::Location, info)
         })
-    }).flatten().collect();
-    locations.iter().min().unwrap().to_string()
-}
\ No newline at end of file
+    }).collect::<Result<_>>()?;
+    locations.iter().min().map(|n| (*n).into()).ok_or_else(|| Error::Solve("no seeds".to_string()))
+}
+
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    const DAY: u16 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    type Part1Input = SeedInfo;
+    type Part2Input = SeedInfo;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+
+    fn part2_variants() -> Vec<Variant<Self::Part2Input>> {
+        vec![("fast", solve_part2), ("naive", solve_part2_naive)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(35));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(46));
+    }
+
+    #[test]
+    fn part2_variants_agree() {
+        let almanac = parse_input(&Input::from_text(EXAMPLE)).unwrap();
+        assert_eq!(solve_part2_naive(&almanac).unwrap(), solve_part2(&almanac).unwrap());
+    }
+
+    #[test]
+    fn part2_moves_further_than_an_i64() {
+        let almanac = parse_input(&Input::from_text("\
+seeds: 18446744073709551000 5
+
+seed-to-location map:
+0 18446744073709551000 10
+")).unwrap();
+        assert_eq!(solve_part2(&almanac).unwrap(), 0.into());
+        assert_eq!(solve_part2_naive(&almanac).unwrap(), 0.into());
+    }
+
+    #[test]
+    fn parses_crlf_sections() {
+        let almanac = parse_input(&Input::from_text(EXAMPLE.replace('\n', "\r\n"))).unwrap();
+        assert_eq!(almanac.seeds, [79, 14, 55, 13]);
+        assert_eq!(almanac.maps.len(), 7);
+    }
+}
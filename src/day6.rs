@@ -1,104 +1,184 @@
-use std::path::Path;
-use crate::common::get_trimmed_lines;
+use crate::common::{parse, Input, Line};
 use itertools::Itertools;
+use crate::error::{Error, ParseResult, Result};
+use crate::solution::{Answer, Puzzle, Variant};
 
-struct Race {
-    time: u64,
-    distance: u64,
+#[derive(Clone, Debug)]
+pub struct Race {
+    pub time: u64,
+    pub distance: u64,
 }
 
 
-fn _parse_part1(file_path: &Path) -> (Vec<u64>, Vec<u64>) {
+/// Reads the `Time:` and `Distance:` lines, using `read` to turn the text after each label into
+/// numbers.
+fn parse_races(input: &Input, read: fn(&Line, &str) -> ParseResult<Vec<u64>>) -> Result<Vec<Race>> {
     let mut time = None;
     let mut distance = None;
 
-    for line in get_trimmed_lines(file_path) {
-        let mut pieces = line.split_whitespace();
-        let first = pieces.next().unwrap();
-        let numbers: Vec<u64> = pieces.into_iter().map(|s| s.parse().unwrap()).collect();
+    for line in input.trimmed_lines() {
+        let (first, rest) = parse::split_pair(&line, &line, ":", "expected `<label>: <numbers>`")?;
+        let numbers = read(&line, rest)?;
 
-        if first == "Time:" {
+        if first == "Time" {
             time = Some(numbers);
-        } else if first == "Distance:" {
+        } else if first == "Distance" {
             distance = Some(numbers);
         } else {
-            panic!("unexpected");
+            return Err(line.error_at(first, "expected `Time:` or `Distance:`").into());
         }
     }
 
-    (time.unwrap(), distance.unwrap())
+    match (time, distance) {
+        (Some(time), Some(distance)) if time.len() == distance.len() => {
+            Ok(time.into_iter().zip(distance).map(|(time, distance)| Race { time, distance }).collect())
+        },
+        (Some(_), Some(_)) => Err(Error::Solve("`Time:` and `Distance:` have different lengths".to_string())),
+        _ => Err(Error::Solve("expected both `Time:` and `Distance:` lines".to_string())),
+    }
 }
-fn parse_input_part1(file_path: &Path) -> Vec<Race> {
-    let (time, distance) = _parse_part1(file_path);
 
-    let combined = (0..time.len()).into_iter().map(|idx| {
-        Race {
-            time: *time.get(idx).unwrap(),
-            distance: *distance.get(idx).unwrap(),
-        }
-    }).collect();
-    combined
+/// The kerning is wrong: all the digits after a label make one number.
+fn kerned_number(line: &Line, s: &str) -> ParseResult<Vec<u64>> {
+    let joined: String = s.split_whitespace().join("");
+    Ok(vec![parse::number(line, &joined)?])
+}
+
+pub fn parse_input_part1(input: &Input) -> Result<Vec<Race>> {
+    parse_races(input, parse::numbers)
+}
+
+pub fn parse_input_part2(input: &Input) -> Result<Vec<Race>> {
+    parse_races(input, kerned_number)
 }
 
-fn calc_time(seconds_held_down: u64, seconds_total: u64) -> u64 {
+pub fn calc_time(seconds_held_down: u64, seconds_total: u64) -> u64 {
     let seconds_released = seconds_total - seconds_held_down;
     seconds_held_down * seconds_released
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let races = parse_input_part1(file_path);
-
-    let result: u64 = races.iter().map(|race| {
-        (0..=race.time).filter(|ms| {
-            calc_time(*ms, race.time) > race.distance
-        }).count()
-    }).product::<usize>() as u64;
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input_part1(input)?)
+}
 
-    result.to_string()
+pub fn solve_part1(races: &[Race]) -> Result<Answer> {
+    solve_fast(races)
 }
 
-fn _parse_part2(file_path: &Path) -> (Vec<u64>, Vec<u64>) {
-    let mut time = None;
-    let mut distance = None;
+/// Tries every hold time.
+pub fn count_wins_naive(race: &Race) -> u64 {
+    (0..=race.time).filter(|ms| {
+        calc_time(*ms, race.time) > race.distance
+    }).count() as u64
+}
 
-    for line in get_trimmed_lines(file_path) {
-        let mut pieces = line.split_whitespace();
-        let first = pieces.next().unwrap();
-        let numbers: Vec<u64> = Vec::from(&[pieces.into_iter().join("").parse().unwrap()]);
+/// Holding for `h` wins when `h * (time - h) > distance`, so the winning hold times are the
+/// integers strictly between the roots of `h^2 - time*h + distance`.
+pub fn count_wins(race: &Race) -> u64 {
+    let (time, distance) = (race.time as u128, race.distance as u128);
+    let wins = |hold: u128| hold <= time && hold * (time - hold) > distance;
+    let discriminant = (time * time) as f64 - 4.0 * distance as f64;
+    if discriminant < 0.0 {
+        return 0;
+    }
 
-        if first == "Time:" {
-            time = Some(numbers);
-        } else if first == "Distance:" {
-            distance = Some(numbers);
-        } else {
-            panic!("unexpected");
-        }
+    // the float root is only a guess at this size, so nudge it onto the exact boundary
+    let mut first = ((time as f64 - discriminant.sqrt()) / 2.0).max(0.0) as u128;
+    while first > 0 && wins(first - 1) {
+        first -= 1;
+    }
+    while first <= time / 2 && !wins(first) {
+        first += 1;
+    }
+    if first > time / 2 {
+        return 0;
     }
+    // the winning range is symmetric around time / 2
+    (time - 2 * first + 1) as u64
+}
 
-    (time.unwrap(), distance.unwrap())
+// generic over the container so these can be listed as variants of a `Vec<Race>` input
+pub fn solve_naive<R: AsRef<[Race]> + ?Sized>(races: &R) -> Result<Answer> {
+    Ok(races.as_ref().iter().map(count_wins_naive).product::<u64>().into())
 }
 
-fn parse_input_part2(file_path: &Path) -> Vec<Race> {
-    let (time, distance) = _parse_part2(file_path);
+pub fn solve_fast<R: AsRef<[Race]> + ?Sized>(races: &R) -> Result<Answer> {
+    Ok(races.as_ref().iter().map(count_wins).product::<u64>().into())
+}
 
-    let combined = (0..time.len()).into_iter().map(|idx| {
-        Race {
-            time: *time.get(idx).unwrap(),
-            distance: *distance.get(idx).unwrap(),
-        }
-    }).collect();
-    combined
+/// Both parts are the same problem, so they share solvers.
+fn variants() -> Vec<Variant<Vec<Race>>> {
+    vec![("fast", solve_fast), ("naive", solve_naive)]
 }
 
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input_part2(input)?)
+}
+
+pub fn solve_part2(races: &[Race]) -> Result<Answer> {
+    solve_fast(races)
+}
 
-pub fn part2(file_path: &Path) -> String {
-    let races = parse_input_part2(file_path);
+pub struct Day6;
 
-    let result: u64 = races.iter().map(|race| {
-        (0..=race.time).filter(|ms| {
-            calc_time(*ms, race.time) > race.distance
-        }).count()
-    }).product::<usize>() as u64;
+impl Puzzle for Day6 {
+    const DAY: u16 = 6;
+    const TITLE: &'static str = "Wait For It";
 
-    result.to_string()
-}
\ No newline at end of file
+    type Part1Input = Vec<Race>;
+    type Part2Input = Vec<Race>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input_part1(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input_part2(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+
+    fn part1_variants() -> Vec<Variant<Self::Part1Input>> {
+        variants()
+    }
+
+    fn part2_variants() -> Vec<Variant<Self::Part2Input>> {
+        variants()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(288));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(71503));
+    }
+
+    #[test]
+    fn count_wins_matches_naive() {
+        for time in 0..60 {
+            for distance in 0..time * time / 4 + 2 {
+                let race = Race { time, distance };
+                assert_eq!(count_wins(&race), count_wins_naive(&race), "{:?}", race);
+            }
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::path::Path;
-use std::sync::mpsc::TrySendError::Full;
-use crate::common::get_trimmed_lines;
+use crate::common::{parse, Input};
 use crate::day7::HandType::{FiveofaKind, FourofaKind, FullHouse, HighCard, OnePair, ThreeofaKind, TwoPair};
+use crate::error::{ParseResult, Result};
+use crate::solution::{Answer, Puzzle};
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandType {
     FiveofaKind = 7,
     FourofaKind = 6,
     FullHouse = 5,
@@ -16,8 +16,8 @@ enum HandType {
     HighCard = 1,
 }
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-enum CardPart1 {
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CardPart1 {
     A = 14,
     K = 13,
     Q = 12,
@@ -33,19 +33,20 @@ enum CardPart1 {
     _2 = 2,
 }
 
-struct HandPart1 {
-    hand: Vec<CardPart1>,
-    handtype: HandType,
-    bid: u64,
+#[derive(Clone, Debug)]
+pub struct HandPart1 {
+    pub hand: Vec<CardPart1>,
+    pub handtype: HandType,
+    pub bid: u64,
 }
 
-fn get_hand_type_part1(hand: &Vec<CardPart1>) -> HandType {
+pub fn get_hand_type_part1(hand: &[CardPart1]) -> HandType {
     let mut counter: HashMap<CardPart1, usize> = HashMap::new();
     for card in hand.iter() {
         counter.entry(*card).and_modify(|v| *v += 1 ).or_insert(1);
     }
 
-    let mut sizes: Vec<usize> = counter.values().map(|v| *v).collect();
+    let mut sizes: Vec<usize> = counter.values().copied().collect();
     sizes.sort();
     sizes.reverse();
 
@@ -74,11 +75,15 @@ fn comparator_part1(a: &HandPart1, b: &HandPart1) -> Ordering {
     a.handtype.cmp(&b.handtype).then(a.hand.cmp(&b.hand))
 }
 
-fn parse_input_part1(file_path: &Path) -> Vec<HandPart1> {
-    get_trimmed_lines(file_path).iter().map(|line| {
+pub fn parse_input_part1(input: &Input) -> Result<Vec<HandPart1>> {
+    input.trimmed_lines().iter().map(|line| {
         let mut pieces = line.split_whitespace();
-        let cards: Vec<CardPart1> = pieces.next().unwrap().chars().map(|c| {
-            match c {
+        let hand = pieces.next().unwrap_or_default();
+        if hand.chars().count() != 5 {
+            return Err(line.error_at(hand, "expected a hand of five cards"));
+        }
+        let cards: Vec<CardPart1> = hand.char_indices().map(|(idx, c)| {
+            Ok(match c {
                 'A' => CardPart1::A,
                 'K' => CardPart1::K,
                 'Q' => CardPart1::Q,
@@ -92,31 +97,38 @@ fn parse_input_part1(file_path: &Path) -> Vec<HandPart1> {
                 '4' => CardPart1::_4,
                 '3' => CardPart1::_3,
                 '2' => CardPart1::_2,
-                _ => panic!("unexpected character")
-            }
-        }).collect();
-        let bid = pieces.next().unwrap().parse().unwrap();
+                _ => return Err(line.error_at(&hand[idx..idx + c.len_utf8()], "unexpected card"))
+            })
+        }).collect::<ParseResult<_>>()?;
+        let bid = match pieces.next() {
+            Some(bid) => parse::number(line, bid)?,
+            None => return Err(line.error("expected a bid after the hand")),
+        };
         let handtype = get_hand_type_part1(&cards);
-        HandPart1 {
+        Ok(HandPart1 {
             hand: cards,
-            handtype: handtype,
+            handtype,
             bid,
-        }
-    }).collect()
+        })
+    }).collect::<ParseResult<_>>().map_err(Into::into)
+}
+
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input_part1(input)?)
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let mut hands = parse_input_part1(file_path);
-    hands.sort_by(comparator_part1);
+pub fn solve_part1(hands: &[HandPart1]) -> Result<Answer> {
+    let mut hands: Vec<&HandPart1> = hands.iter().collect();
+    hands.sort_by(|a, b| comparator_part1(a, b));
     let winnings: usize = hands.iter().enumerate().map(|(i, v)| {
         (i + 1) * (v.bid as usize)
     }).sum();
-    winnings.to_string()
+    Ok(winnings.into())
 }
 
 
-#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-enum CardPart2 {
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CardPart2 {
     A = 14,
     K = 13,
     Q = 12,
@@ -132,13 +144,14 @@ enum CardPart2 {
     J = 1,
 }
 
-struct HandPart2 {
-    hand: Vec<CardPart2>,
-    handtype: HandType,
-    bid: u64,
+#[derive(Clone, Debug)]
+pub struct HandPart2 {
+    pub hand: Vec<CardPart2>,
+    pub handtype: HandType,
+    pub bid: u64,
 }
 
-fn get_hand_type_part2(hand: &Vec<CardPart2>) -> HandType {
+pub fn get_hand_type_part2(hand: &[CardPart2]) -> HandType {
     let mut counter: HashMap<CardPart2, usize> = HashMap::new();
     for card in hand.iter() {
         counter.entry(*card).and_modify(|v| *v += 1 ).or_insert(1);
@@ -146,11 +159,11 @@ fn get_hand_type_part2(hand: &Vec<CardPart2>) -> HandType {
     let num_jokers = *counter.get(&CardPart2::J).unwrap_or(&0);
     counter.remove(&CardPart2::J);
 
-    let mut sizes: Vec<usize> = counter.values().map(|v| *v).collect();
+    let mut sizes: Vec<usize> = counter.values().copied().collect();
     sizes.sort();
     sizes.reverse();
 
-    if sizes.len() > 0 {
+    if !sizes.is_empty() {
         sizes[0] += num_jokers;
     } else {
         sizes.push(num_jokers);
@@ -181,11 +194,15 @@ fn comparator_part2(a: &HandPart2, b: &HandPart2) -> Ordering {
     a.handtype.cmp(&b.handtype).then(a.hand.cmp(&b.hand))
 }
 
-fn parse_input_part2(file_path: &Path) -> Vec<HandPart2> {
-    get_trimmed_lines(file_path).iter().map(|line| {
+pub fn parse_input_part2(input: &Input) -> Result<Vec<HandPart2>> {
+    input.trimmed_lines().iter().map(|line| {
         let mut pieces = line.split_whitespace();
-        let cards: Vec<CardPart2> = pieces.next().unwrap().chars().map(|c| {
-            match c {
+        let hand = pieces.next().unwrap_or_default();
+        if hand.chars().count() != 5 {
+            return Err(line.error_at(hand, "expected a hand of five cards"));
+        }
+        let cards: Vec<CardPart2> = hand.char_indices().map(|(idx, c)| {
+            Ok(match c {
                 'A' => CardPart2::A,
                 'K' => CardPart2::K,
                 'Q' => CardPart2::Q,
@@ -199,25 +216,80 @@ fn parse_input_part2(file_path: &Path) -> Vec<HandPart2> {
                 '4' => CardPart2::_4,
                 '3' => CardPart2::_3,
                 '2' => CardPart2::_2,
-                _ => panic!("unexpected character")
-            }
-        }).collect();
-        let bid = pieces.next().unwrap().parse().unwrap();
+                _ => return Err(line.error_at(&hand[idx..idx + c.len_utf8()], "unexpected card"))
+            })
+        }).collect::<ParseResult<_>>()?;
+        let bid = match pieces.next() {
+            Some(bid) => parse::number(line, bid)?,
+            None => return Err(line.error("expected a bid after the hand")),
+        };
         let handtype = get_hand_type_part2(&cards);
-        HandPart2 {
+        Ok(HandPart2 {
             hand: cards,
-            handtype: handtype,
+            handtype,
             bid,
-        }
-    }).collect()
+        })
+    }).collect::<ParseResult<_>>().map_err(Into::into)
 }
 
-pub fn part2(file_path: &Path) -> String {
-    let mut hands = parse_input_part2(file_path);
-    hands.sort_by(comparator_part2);
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input_part2(input)?)
+}
+
+pub fn solve_part2(hands: &[HandPart2]) -> Result<Answer> {
+    let mut hands: Vec<&HandPart2> = hands.iter().collect();
+    hands.sort_by(|a, b| comparator_part2(a, b));
     let winnings: usize = hands.iter().enumerate().map(|(i, v)| {
         (i + 1) * (v.bid as usize)
     }).sum();
-    winnings.to_string()
+    Ok(winnings.into())
+}
+
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    const DAY: u16 = 7;
+    const TITLE: &'static str = "Camel Cards";
+
+    type Part1Input = Vec<HandPart1>;
+    type Part2Input = Vec<HandPart2>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input_part1(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input_part2(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(6440));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(5905));
+    }
+}
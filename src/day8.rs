@@ -1,18 +1,20 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::Path;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::fmt;
 use std::fmt::Formatter;
-use crate::common::get_trimmed_lines;
+use crate::common::{math, parse, Input, Line};
+use crate::error::{Error, ParseResult, Result};
+use crate::solution::{Answer, Puzzle};
 
-#[derive(Copy, Clone)]
-enum Instruction {
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
     Left,
     Right,
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
-struct NodeKey {
-    key: (u8, u8, u8)
+pub struct NodeKey {
+    pub key: (u8, u8, u8)
 }
 
 impl fmt::Debug for NodeKey {
@@ -31,47 +33,60 @@ impl NodeKey {
     }
 }
 
-struct Instructions {
-    instructions: Vec<Instruction>,
-    node_map: HashMap<NodeKey, (NodeKey, NodeKey)>,
+#[derive(Clone, Debug)]
+pub struct Instructions {
+    pub instructions: Vec<Instruction>,
+    pub node_map: HashMap<NodeKey, (NodeKey, NodeKey)>,
 }
 
-fn _to_node_key(s: &str) -> NodeKey {
-    if s.len() != 3 {
-        panic!("unexpected");
-    }
-    let key_string_bytes: Vec<u8> = s.bytes().collect();
-    NodeKey { key: (key_string_bytes[0], key_string_bytes[1], key_string_bytes[2]) }
+fn _to_node_key(line: &Line, s: &str) -> ParseResult<NodeKey> {
+    let [a, b, c] = parse::fixed_key(line, s)?;
+    Ok(NodeKey { key: (a, b, c) })
 }
 
-fn parse_input(file_path: &Path) -> Instructions {
-    let lines = get_trimmed_lines(file_path);
+pub fn parse_input(input: &Input) -> Result<Instructions> {
+    let lines = input.trimmed_lines();
     let mut lines_iter = lines.iter();
-    let instructions_chars = lines_iter.next().unwrap().chars();
-    let instructions = instructions_chars.map(|c| {
+    let first_line = lines_iter.next().ok_or_else(|| Error::Solve("input is empty".to_string()))?;
+    let instructions = first_line.char_indices().map(|(idx, c)| {
         match c {
-            'R' => Instruction::Right,
-            'L' => Instruction::Left,
-            _ => panic!("unknown character")
+            'R' => Ok(Instruction::Right),
+            'L' => Ok(Instruction::Left),
+            _ => Err(first_line.error_at(&first_line[idx..idx + c.len_utf8()], "expected `L` or `R`"))
         }
-    }).collect();
+    }).collect::<ParseResult<_>>()?;
 
     let mut node_map: HashMap<NodeKey, (NodeKey, NodeKey)> = HashMap::new();
+    let mut targets = Vec::new();
     for line in lines_iter {
-        let pieces: Vec<&str> = line.split(" = ").collect();
-        let key = _to_node_key(pieces.get(0).unwrap());
-        let value: Vec<&str> = pieces.get(1).unwrap().trim_matches(|c| { c == '(' || c == ')' }).split(", ").collect();
-        node_map.insert(key, (_to_node_key(value[0]), _to_node_key(value[1])));
+        let (key, value) = parse::split_pair(line, line, " = ", "expected `KEY = (LEFT, RIGHT)`")?;
+        let key = _to_node_key(line, key)?;
+        let (left, right) = parse::split_pair(line, parse::enclosed(line, value, '(', ')')?, ", ", "expected `(LEFT, RIGHT)`")?;
+        node_map.insert(key, (_to_node_key(line, left)?, _to_node_key(line, right)?));
+        targets.extend([(line, left), (line, right)]);
     }
 
-    Instructions {
-        instructions: instructions,
-        node_map: node_map,
+    // every node a step can lead to has to be defined, or walking the map would dead-end
+    for (line, target) in targets {
+        if !node_map.contains_key(&_to_node_key(line, target)?) {
+            return Err(line.error_at(target, format!("node `{}` is never defined", target)).into());
+        }
     }
+
+    Ok(Instructions {
+        instructions,
+        node_map,
+    })
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let instructions = parse_input(file_path);
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input(input)?)
+}
+
+pub fn solve_part1(instructions: &Instructions) -> Result<Answer> {
+    if instructions.instructions.is_empty() {
+        return Err(Error::Solve("no instructions".to_string()));
+    }
     let mut current = NodeKey { key: (b'A', b'A', b'A') };
     let goal = NodeKey{ key: (b'Z', b'Z', b'Z') };
 
@@ -79,8 +94,9 @@ pub fn part1(file_path: &Path) -> String {
     let mut instruction_index = 0;
     while current != goal {
         num_steps += 1;
-        //println!("{:?} {:?}", instructions.node_map, current);
-        let (left, right) = instructions.node_map.get(&current).unwrap();
+        let (left, right) = instructions.node_map.get(&current).ok_or_else(|| {
+            Error::Solve(format!("no node {:?}", current))
+        })?;
         let instruction = instructions.instructions[instruction_index];
         let next = *match instruction {
             Instruction::Left => left,
@@ -93,7 +109,7 @@ pub fn part1(file_path: &Path) -> String {
         }
     }
 
-    num_steps.to_string()
+    Ok(num_steps.into())
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -110,14 +126,7 @@ impl Iterator for IteratorState {
         let current = self.current_num_steps;
         self.current_num_steps = match self.current_num_steps {
             Some(_current) => {
-                match self.inc {
-                    Some(_inc) => {
-                        Some(_current + _inc)
-                    },
-                    None => {
-                        None
-                    }
-                }
+                self.inc.map(|_inc| _current + _inc)
             },
             None => {
                 None
@@ -127,25 +136,7 @@ impl Iterator for IteratorState {
     }
 }
 
-struct IteratorDeque {
-    iterator_state: IteratorState,
-    deque: VecDeque<usize>,
-}
-
-impl IteratorDeque {
-    pub fn peek(&mut self) -> usize {
-        if self.deque.len() == 0 {
-            self.deque.push_front(self.iterator_state.next().unwrap());
-        }
-        *self.deque.get(0).unwrap()
-    }
-
-    pub fn remove_first(&mut self) {
-        self.deque.pop_front();
-    }
-}
-
-fn calc_iterators(starts: &Vec<NodeKey>, instructions: &Instructions) -> HashMap<NodeKey, HashMap<(NodeKey, usize), IteratorState>> {
+fn calc_iterators(starts: &[NodeKey], instructions: &Instructions) -> HashMap<NodeKey, HashMap<(NodeKey, usize), IteratorState>> {
     let mut recurrence_first: HashMap<NodeKey, HashMap<(NodeKey, usize), usize>> = HashMap::new();
     let mut recurrence_second: HashMap<NodeKey, HashMap<(NodeKey, usize), usize>> = HashMap::new();
 
@@ -166,10 +157,10 @@ fn calc_iterators(starts: &Vec<NodeKey>, instructions: &Instructions) -> HashMap
                 }
             };
 
-            if !first_map.contains_key(&(next, instruction_index)) {
-                first_map.insert((next, instruction_index), num_steps + 1);
-            } else if !second_map.contains_key(&(next, instruction_index)) {
-                second_map.insert((next, instruction_index), num_steps + 1);
+            if let Entry::Vacant(e) = first_map.entry((next, instruction_index)) {
+                e.insert(num_steps + 1);
+            } else if let Entry::Vacant(e) = second_map.entry((next, instruction_index)) {
+                e.insert(num_steps + 1);
             } else {
                 break;
             }
@@ -197,41 +188,65 @@ fn calc_iterators(starts: &Vec<NodeKey>, instructions: &Instructions) -> HashMap
             iters.insert(*first_key, IteratorState {
                 initial_num_steps: *first_value,
                 current_num_steps: Some(*first_value),
-                inc: match second_value {
-                    Some(_second_value) => {
-                        Some(_second_value - first_value)
-                    },
-                    None => None
-                }
+                inc: second_value.map(|_second_value| _second_value - first_value)
             });
         }
-        //println!("start {:?} {:?}", start, iters);
+        log::trace!("start {:?} {:?}", start, iters);
         (*start, iters)
     }).collect();
 
     iterators
 }
 
-fn _factorize(n: usize, factors: &mut HashSet<usize>) {
-    for i in 2..(n - 1) {
-        if n % i == 0 {
-            factors.insert(i);
-            return _factorize(n / i, factors);
+/// When a ghost stands on an end node: at step `first`, then every `period` steps after that if the
+/// end is part of the cycle it settles into.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Arrival {
+    first: u64,
+    period: Option<u64>,
+}
+
+impl Arrival {
+    fn hits(&self, step: u64) -> bool {
+        match self.period {
+            _ if step < self.first => false,
+            Some(period) => (step - self.first).is_multiple_of(period),
+            None => step == self.first,
         }
     }
-    factors.insert(n);
 }
 
-fn factorize(n: usize) -> HashSet<usize> {
-    let mut factors = HashSet::new();
-
-    _factorize(n, &mut factors);
+/// The steps at which both `a` and `b` happen, as a single arrival, or `None` if there are none.
+fn coincide(a: Arrival, b: Arrival) -> Result<Option<Arrival>> {
+    let overflow = || Error::Solve("the ghosts' cycles don't line up within a u64 step count".to_string());
+    match (a.period, b.period) {
+        (Some(period_a), Some(period_b)) => {
+            let period = math::lcm(period_a, period_b).ok_or_else(overflow)?;
+            let Some((residue, _)) = math::crt([(a.first % period_a, period_a), (b.first % period_b, period_b)]) else {
+                return Ok(None);
+            };
+            // the earliest solution both ghosts have actually reached
+            let earliest = a.first.max(b.first);
+            let first = if residue >= earliest {
+                residue
+            } else {
+                (earliest - residue).div_ceil(period).checked_mul(period).and_then(|n| n.checked_add(residue)).ok_or_else(overflow)?
+            };
+            Ok(Some(Arrival { first, period: Some(period) }))
+        },
+        (None, _) => Ok(b.hits(a.first).then_some(a)),
+        (_, None) => Ok(a.hits(b.first).then_some(b)),
+    }
+}
 
-    factors
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input(input)?)
 }
 
-pub fn part2(file_path: &Path) -> String {
-    let instructions = parse_input(file_path);
+pub fn solve_part2(instructions: &Instructions) -> Result<Answer> {
+    if instructions.instructions.is_empty() {
+        return Err(Error::Solve("no instructions".to_string()));
+    }
 
     let starts: Vec<NodeKey> = instructions.node_map.keys().filter_map(|s| {
         if s.endswith(b'A') {
@@ -240,26 +255,166 @@ pub fn part2(file_path: &Path) -> String {
             None
         }
     }).collect();
+    if starts.is_empty() {
+        return Err(Error::Solve("no start node ends in `A`".to_string()));
+    }
 
-    let iterator_map = calc_iterators(&starts, &instructions);
+    let iterator_map = calc_iterators(&starts, instructions);
 
-    let mut combined_factors = HashSet::new();
+    // every step count at which all the ghosts so far stand on an end, as a set of arrivals
+    let mut together = vec![Arrival { first: 0, period: Some(1) }];
     for (start, end_map) in iterator_map.iter() {
-        for (end_tup, end_iter) in end_map.iter() {
-            if end_tup.0.endswith(b'Z') {
-                println!("Starting from {:?} to {:?}, {:?} + {:?}x", start, end_tup, end_iter.initial_num_steps, end_iter.inc);
-
-                if end_iter.inc.unwrap() != end_iter.initial_num_steps {
-                    // the results showed all inc == initial for whatever reason, so just the case where it isn't for simplicity
-                    panic!("unexpected");
-                }
+        let arrivals: Vec<Arrival> = end_map.iter().filter(|(end_tup, _)| end_tup.0.endswith(b'Z')).map(|(end_tup, end_iter)| {
+            log::debug!("Starting from {:?} to {:?}, {:?} + {:?}x", start, end_tup, end_iter.initial_num_steps, end_iter.inc);
+            Arrival {
+                first: end_iter.initial_num_steps as u64,
+                period: end_iter.inc.map(|inc| inc as u64),
+            }
+        }).collect();
+        if arrivals.is_empty() {
+            return Err(Error::Solve(format!("no end is reachable from {:?}", start)));
+        }
 
-                let factors = factorize(end_iter.inc.unwrap());
-                println!("factors {:?}", factors);
-                combined_factors.extend(factors);
+        let mut next = Vec::new();
+        for current in &together {
+            for arrival in &arrivals {
+                next.extend(coincide(*current, *arrival)?);
             }
         }
+        together = next;
+    }
+
+    let first = together.iter().map(|arrival| arrival.first).min()
+        .ok_or_else(|| Error::Solve("the ghosts never all stand on an end at once".to_string()))?;
+    Ok(first.into())
+}
+
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    const DAY: u16 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    type Part1Input = Instructions;
+    type Part2Input = Instructions;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_REPEATING: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_PART2: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(2));
+    }
+
+    #[test]
+    fn part1_example_repeating() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE_REPEATING)).unwrap(), Answer::from(6));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE_PART2)).unwrap(), Answer::from(6));
+    }
+
+    #[test]
+    fn part2_cycles_that_are_not_squarefree() {
+        // 11A reaches 11Z every 4 steps and 22A reaches 22Z every 3; the old product of distinct
+        // prime factors gave 2 * 3
+        let input = "\
+L
+
+11A = (11B, XXX)
+11B = (11C, XXX)
+11C = (11D, XXX)
+11D = (11Z, XXX)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, XXX)
+22C = (22Z, XXX)
+22Z = (22B, XXX)
+XXX = (XXX, XXX)
+";
+        assert_eq!(part2(&Input::from_text(input)).unwrap(), Answer::from(12));
     }
 
-    combined_factors.iter().product::<usize>().to_string()
+    #[test]
+    fn part2_offset_cycles() {
+        // 11Z first at step 2 then every 3 steps (2, 5, 8, ...), 22Z at 1, 3, 5, ...
+        let input = "\
+L
+
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11C, XXX)
+11C = (11D, XXX)
+11D = (11Z, XXX)
+22A = (22Z, XXX)
+22Z = (22B, XXX)
+22B = (22Z, XXX)
+XXX = (XXX, XXX)
+";
+        assert_eq!(part2(&Input::from_text(input)).unwrap(), Answer::from(5));
+    }
+
+    #[test]
+    fn undefined_node_is_an_error() {
+        let input = Input::from_text("L\n\n11A = (11B, XXX)\n");
+        let err = match part2(&input) {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 8, "11B"));
+        assert!(matches!(part1(&input), Err(Error::Parse(_))));
+    }
 }
@@ -1,17 +1,16 @@
-use std::path::Path;
-use crate::common::get_trimmed_lines;
+use crate::common::{parse, Input};
+use crate::error::Result;
+use crate::solution::{Answer, Puzzle};
 
-fn parse_input(file_path: &Path) -> Vec<Vec<i64>> {
-    let lines = get_trimmed_lines(file_path);
+pub fn parse_input(input: &Input) -> Result<Vec<Vec<i64>>> {
+    let lines = input.trimmed_lines();
 
-    lines.iter().map(|line| {
-        line.split_whitespace().map(|n| n.parse::<i64>().unwrap()).collect()
-    }).collect()
+    lines.iter().map(|line| parse::numbers(line, line).map_err(Into::into)).collect()
 }
 
-fn calc_history_next(nums: &Vec<i64>) -> i64 {
+pub fn calc_history_next(nums: &[i64]) -> i64 {
     let mut triangle: Vec<Vec<i64>> = Vec::new();
-    triangle.push(nums.clone());
+    triangle.push(nums.to_vec());
 
     while let Some(last) = triangle.last() {
         if last.iter().all(|n| *n == 0) {
@@ -27,24 +26,26 @@ fn calc_history_next(nums: &Vec<i64>) -> i64 {
 
     let mut history = 0;
     for row in triangle.iter().rev().skip(1) {
-        history = history + row.last().unwrap();
+        history += row.last().unwrap();
     }
     history
 }
 
-pub fn part1(file_path: &Path) -> String {
-    let mut nums = parse_input(file_path);
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input(input)?)
+}
 
+pub fn solve_part1(nums: &[Vec<i64>]) -> Result<Answer> {
     let histories: Vec<_> = nums.iter().map(|n| {
         calc_history_next(n)
     }).collect();
 
-    histories.iter().sum::<i64>().to_string()
+    Ok(histories.iter().sum::<i64>().into())
 }
 
-fn calc_history_prev(nums: &Vec<i64>) -> i64 {
+pub fn calc_history_prev(nums: &[i64]) -> i64 {
     let mut triangle: Vec<Vec<i64>> = Vec::new();
-    triangle.push(nums.clone());
+    triangle.push(nums.to_vec());
 
     while let Some(last) = triangle.last() {
         if last.iter().all(|n| *n == 0) {
@@ -63,19 +64,68 @@ fn calc_history_prev(nums: &Vec<i64>) -> i64 {
     for row in triangle.iter().skip(1) {
         // new-history + old-history = row.first().unwrap()
 
-        println!("{:?} - {:?} = {:?}", row.first().unwrap(), history, row.first().unwrap() - history);
+        log::trace!("{:?} - {:?} = {:?}", row.first().unwrap(), history, row.first().unwrap() - history);
         history = row.first().unwrap() - history;
     }
-    println!("---");
+    log::trace!("---");
     history
 }
 
-pub fn part2(file_path: &Path) -> String {
-    let mut nums = parse_input(file_path);
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input(input)?)
+}
 
+pub fn solve_part2(nums: &[Vec<i64>]) -> Result<Answer> {
     let histories: Vec<_> = nums.iter().map(|n| {
         calc_history_prev(n)
     }).collect();
 
-    histories.iter().sum::<i64>().to_string()
+    Ok(histories.iter().sum::<i64>().into())
+}
+
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    const DAY: u16 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    type Part1Input = Vec<Vec<i64>>;
+    type Part2Input = Vec<Vec<i64>>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(114));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(2));
+    }
 }
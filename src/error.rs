@@ -0,0 +1,102 @@
+use std::fmt;
+use std::io;
+
+/// A parse failure pinned to a location in the puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Input name: a path, `<stdin>` or `<inline>`.
+    pub file: Option<String>,
+    /// 1-based line number in the original input, blank lines included.
+    pub line: usize,
+    /// 1-based column, counted in chars.
+    pub column: usize,
+    /// The offending text, or the whole line if no narrower span was available.
+    pub text: String,
+    pub message: String,
+    /// The full, untrimmed source line, kept for rendering diagnostics.
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// Multi-line, rustc-style rendering with the source line and a caret under the offending text.
+    pub fn diagnostic(&self) -> String {
+        let location = match &self.file {
+            Some(file) => format!("{}:{}:{}", file, self.line, self.column),
+            None => format!("<input>:{}:{}", self.line, self.column),
+        };
+        let gutter = " ".repeat(self.line.to_string().len());
+        let carets = "^".repeat(self.text.chars().count().max(1));
+        format!(
+            "error: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}{}",
+            self.message,
+            gutter, location,
+            gutter,
+            self.line, self.source_line,
+            gutter, " ".repeat(self.column.saturating_sub(1)), carets,
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}: {} (at `{}`)", self.line, self.column, self.message, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub enum Error {
+    Io { name: String, source: io::Error },
+    Parse(ParseError),
+    /// The input parsed but doesn't have the shape the solver relies on.
+    Solve(String),
+    /// A malformed settings file, such as the answers file.
+    Config(String),
+}
+
+impl Error {
+    /// Fills in the input name on parse errors that were raised without one.
+    pub fn with_file(self, name: &str) -> Error {
+        match self {
+            Error::Parse(mut err) if err.file.is_none() => {
+                err.file = Some(name.to_string());
+                Error::Parse(err)
+            },
+            other => other,
+        }
+    }
+
+    pub fn diagnostic(&self) -> String {
+        match self {
+            Error::Parse(err) => err.diagnostic(),
+            other => format!("error: {}", other),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { name, source } => write!(f, "unable to read {}: {}", name, source),
+            Error::Parse(err) => err.fmt(f),
+            Error::Solve(message) => f.write_str(message),
+            Error::Config(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub type ParseResult<T> = std::result::Result<T, ParseError>;
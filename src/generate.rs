@@ -0,0 +1,352 @@
+//! Synthetic puzzle inputs for stress-testing solvers, via the `generate` subcommand.
+//!
+//! Every generator is driven by a seeded [`Rng`], so the same day, seed and size always give the
+//! same text. `size` scales whatever makes that day expensive: the number of lines for most days,
+//! seed range lengths for day 5, the number of `..A` starts for day 8 (at most 14, see
+//! [`max_size`]) and the maze side for day 10.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use crate::error::{Error, Result};
+
+/// splitmix64, which is tiny and good enough for test data. Kept in-tree so the output for a seed
+/// never changes under us.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-ish in `low..high`; `high` must be greater than `low`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low)
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0, len as u64) as usize
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// Size used when `--size` isn't given: big enough to be interesting, small enough to finish.
+pub fn default_size(day: u16) -> usize {
+    match day {
+        4 => 200,
+        5 => 1_000_000,
+        6 => 100,
+        8 => 6,
+        _ => 1000,
+    }
+}
+
+/// The largest `size` a day's generator can honour, for the days that have a limit.
+pub fn max_size(day: u16) -> Option<usize> {
+    match day {
+        8 => Some(DAY8_CYCLES.len()),
+        _ => None,
+    }
+}
+
+/// Generates an input for `day`. Fails if there's no generator for it or `size` is over the day's
+/// [`max_size`].
+pub fn generate(day: u16, seed: u64, size: usize) -> Result<String> {
+    if let Some(max) = max_size(day).filter(|&max| size > max) {
+        return Err(Error::Config(format!("day {} inputs can't be larger than size {}, got {}", day, max, size)));
+    }
+    let mut rng = Rng::new(seed ^ ((day as u64) << 56));
+    let size = size.max(1);
+    let text = match day {
+        1 => day1(&mut rng, size),
+        2 => day2(&mut rng, size),
+        3 => day3(&mut rng, size),
+        4 => day4(&mut rng, size),
+        5 => day5(&mut rng, size),
+        6 => day6(&mut rng, size),
+        7 => day7(&mut rng, size),
+        8 => day8(&mut rng, size),
+        9 => day9(&mut rng, size),
+        10 => day10(&mut rng, size),
+        _ => return Err(Error::Config(format!("there's no generator for day {}", day))),
+    };
+    Ok(text)
+}
+
+const NUMBER_WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+fn day1(rng: &mut Rng, lines: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..lines {
+        let mut line = String::new();
+        for _ in 0..rng.range(1, 8) {
+            match rng.range(0, 3) {
+                0 => line.push((b'0' + rng.range(1, 10) as u8) as char),
+                1 => line.push_str(rng.pick(&NUMBER_WORDS)),
+                _ => line.push((b'a' + rng.range(0, 26) as u8) as char),
+            }
+        }
+        // both parts need at least one plain digit on every line
+        line.insert(rng.index(line.len() + 1), (b'0' + rng.range(1, 10) as u8) as char);
+        writeln!(out, "{}", line).unwrap();
+    }
+    out
+}
+
+fn day2(rng: &mut Rng, games: usize) -> String {
+    let mut out = String::new();
+    for game in 1..=games {
+        let draws: Vec<String> = (0..rng.range(1, 7)).map(|_| {
+            let mut colors = vec!["red", "green", "blue"];
+            rng.shuffle(&mut colors);
+            colors.truncate(rng.range(1, 4) as usize);
+            colors.iter().map(|color| format!("{} {}", rng.range(1, 21), color)).collect::<Vec<_>>().join(", ")
+        }).collect();
+        writeln!(out, "Game {}: {}", game, draws.join("; ")).unwrap();
+    }
+    out
+}
+
+fn day3(rng: &mut Rng, side: usize) -> String {
+    let side = side.max(3);
+    let mut grid = vec![vec!['.'; side]; side];
+    for row in grid.iter_mut() {
+        let mut col = 0;
+        while col < side {
+            match rng.range(0, 10) {
+                0..=2 => {
+                    let digits = rng.range(1, 4) as usize;
+                    // keep a gap after each number so they don't run into the next row or number
+                    if col + digits < side {
+                        for cell in &mut row[col..col + digits] {
+                            *cell = (b'0' + rng.range(0, 10) as u8) as char;
+                        }
+                        col += digits;
+                    }
+                },
+                3 => row[col] = rng.pick(&['*', '#', '+', '$', '/', '=', '%', '@', '&', '-']),
+                _ => {},
+            }
+            col += 1;
+        }
+    }
+    grid.iter().map(|row| row.iter().collect::<String>() + "\n").collect()
+}
+
+fn day4(rng: &mut Rng, cards: usize) -> String {
+    const WINNING: usize = 10;
+    const YOURS: usize = 25;
+    let mut out = String::new();
+    for card in 1..=cards {
+        // a card can't win copies of cards past the end of the table, and averaging under one win
+        // per card keeps the number of copies from growing exponentially
+        let wins = if rng.range(0, 10) < 6 { 0 } else { rng.range(1, 4) as usize }.min(cards - card);
+        let mut numbers: Vec<u64> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let winning = &numbers[..WINNING];
+        let mut yours: Vec<u64> = winning[..wins].to_vec();
+        yours.extend(&numbers[WINNING..WINNING + YOURS - wins]);
+        rng.shuffle(&mut yours);
+
+        let list = |numbers: &[u64]| numbers.iter().map(|n| format!("{:>2}", n)).collect::<Vec<_>>().join(" ");
+        writeln!(out, "Card {:>4}: {} | {}", card, list(winning), list(&yours)).unwrap();
+    }
+    out
+}
+
+fn day5(rng: &mut Rng, seed_range_len: usize) -> String {
+    const CHAIN: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+    const SPACE: u64 = 4_000_000_000;
+
+    let seeds: Vec<String> = (0..4).map(|_| {
+        let len = rng.range(seed_range_len as u64 / 2 + 1, seed_range_len as u64 + 2);
+        format!("{} {}", rng.range(0, SPACE - len), len)
+    }).collect();
+    let mut out = format!("seeds: {}\n", seeds.join(" "));
+
+    for pair in CHAIN.windows(2) {
+        writeln!(out, "\n{}-to-{} map:", pair[0], pair[1]).unwrap();
+        // non-overlapping source ranges, each sent somewhere random
+        let mut cuts: Vec<u64> = (0..20).map(|_| rng.range(0, SPACE)).collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        for bounds in cuts.windows(2) {
+            let len = bounds[1] - bounds[0];
+            writeln!(out, "{} {} {}", rng.range(0, SPACE - len), bounds[0], len).unwrap();
+        }
+    }
+    out
+}
+
+fn day6(rng: &mut Rng, max_time: usize) -> String {
+    let races: Vec<(u64, u64)> = (0..4).map(|_| {
+        let time = rng.range(2, max_time.max(3) as u64);
+        let best = (time / 2) * (time - time / 2);
+        (time, rng.range(0, best))
+    }).collect();
+    let row = |values: Vec<u64>| values.iter().map(|v| format!("{:>6}", v)).collect::<Vec<_>>().join(" ");
+    format!(
+        "Time:     {}\nDistance: {}\n",
+        row(races.iter().map(|race| race.0).collect()),
+        row(races.iter().map(|race| race.1).collect())
+    )
+}
+
+fn day7(rng: &mut Rng, hands: usize) -> String {
+    const CARDS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+    let mut out = String::new();
+    for _ in 0..hands {
+        let hand: String = (0..5).map(|_| rng.pick(&CARDS)).collect();
+        writeln!(out, "{} {}", hand, rng.range(1, 1001)).unwrap();
+    }
+    out
+}
+
+/// Day 8's cycle lengths, one per start, in multiples of the instruction length. The answer is
+/// their lcm, which has to fit in 64 bits, so this also caps how many starts there can be.
+const DAY8_CYCLES: [u64; 14] = [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+/// Each start walks a private chain to its `..Z` node and then around again, so every ghost is on
+/// a cycle whose length equals its first arrival, like the real inputs.
+fn day8(rng: &mut Rng, starts: usize) -> String {
+    let instruction_len = rng.pick(&[7, 11, 13]);
+    let instructions: String = (0..instruction_len).map(|_| if rng.range(0, 2) == 0 { 'L' } else { 'R' }).collect();
+
+    let letter = |rng: &mut Rng, exclude: &[u8]| loop {
+        let c = b'A' + rng.range(0, 26) as u8;
+        if !exclude.contains(&c) {
+            break c as char;
+        }
+    };
+    let mut used: HashSet<String> = ["AAA", "ZZZ"].iter().map(|name| name.to_string()).collect();
+    let mut fresh = |rng: &mut Rng, last: char| loop {
+        let name: String = [letter(rng, &[]), letter(rng, &[]), last].iter().collect();
+        if used.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let mut nodes = Vec::new();
+    for (i, cycle) in DAY8_CYCLES[..starts].iter().enumerate() {
+        let (start, end) = if i == 0 {
+            ("AAA".to_string(), "ZZZ".to_string())
+        } else {
+            (fresh(rng, 'A'), fresh(rng, 'Z'))
+        };
+        let middle: Vec<String> = (1..cycle * instruction_len).map(|_| {
+            let last = letter(rng, b"AZ");
+            fresh(rng, last)
+        }).collect();
+
+        let mut chain = vec![start];
+        chain.extend(middle);
+        chain.push(end.clone());
+        for pair in chain.windows(2) {
+            nodes.push(format!("{} = ({}, {})", pair[0], pair[1], pair[1]));
+        }
+        nodes.push(format!("{} = ({}, {})", end, chain[1], chain[1]));
+    }
+    rng.shuffle(&mut nodes);
+    format!("{}\n\n{}\n", instructions, nodes.join("\n"))
+}
+
+fn day9(rng: &mut Rng, lines: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..lines {
+        let coefficients: Vec<i64> = (0..rng.range(1, 6)).map(|_| rng.range(0, 21) as i64 - 10).collect();
+        let values: Vec<String> = (0..21i64).map(|x| {
+            coefficients.iter().rev().fold(0, |acc, c| acc * x + c).to_string()
+        }).collect();
+        writeln!(out, "{}", values.join(" ")).unwrap();
+    }
+    out
+}
+
+/// A snake-shaped loop filling a `side`-square area, framed by random junk pipes.
+fn day10(rng: &mut Rng, side: usize) -> String {
+    const JUNK: [u8; 7] = [b'|', b'-', b'L', b'J', b'7', b'F', b'.'];
+    let side = side.max(4).div_ceil(2) * 2;
+    let total = side + 2;
+    let mut grid: Vec<Vec<u8>> = (0..total).map(|_| (0..total).map(|_| rng.pick(&JUNK)).collect()).collect();
+
+    // top row left to right, snake back through columns 1.., then up column 0
+    let mut path = Vec::with_capacity(side * side);
+    path.extend((0..side).map(|col| (0, col)));
+    for row in 1..side {
+        if row % 2 == 1 {
+            path.extend((1..side).rev().map(|col| (row, col)));
+        } else {
+            path.extend((1..side).map(|col| (row, col)));
+        }
+    }
+    path.extend((1..side).rev().map(|row| (row, 0)));
+
+    for (i, &(row, col)) in path.iter().enumerate() {
+        let prev = path[(i + path.len() - 1) % path.len()];
+        let next = path[(i + 1) % path.len()];
+        let mut dirs = [prev, next].map(|(r, c)| (r as i64 - row as i64, c as i64 - col as i64));
+        dirs.sort();
+        grid[row + 1][col + 1] = match dirs {
+            [(-1, 0), (1, 0)] => b'|',
+            [(0, -1), (0, 1)] => b'-',
+            [(-1, 0), (0, 1)] => b'L',
+            [(-1, 0), (0, -1)] => b'J',
+            [(0, -1), (1, 0)] => b'7',
+            [(0, 1), (1, 0)] => b'F',
+            _ => unreachable!("loop steps are always to a neighbor"),
+        };
+    }
+    let (row, col) = path[rng.index(path.len())];
+    grid[row + 1][col + 1] = b'S';
+
+    grid.into_iter().map(|row| String::from_utf8(row).unwrap() + "\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Input;
+    use crate::registry;
+
+    #[test]
+    fn same_seed_same_output() {
+        assert_eq!(generate(5, 7, 100).unwrap(), generate(5, 7, 100).unwrap());
+        assert_ne!(generate(5, 7, 100).unwrap(), generate(5, 8, 100).unwrap());
+    }
+
+    #[test]
+    fn sizes_past_the_limit_are_an_error() {
+        assert!(generate(8, 1, DAY8_CYCLES.len()).is_ok());
+        assert!(matches!(generate(8, 1, DAY8_CYCLES.len() + 1), Err(Error::Config(_))));
+        assert!(matches!(generate(99, 1, 10), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        for solution in registry::SOLUTIONS {
+            let size = max_size(solution.day()).map_or(20, |max| max.min(20));
+            let text = generate(solution.day(), 1, size).unwrap();
+            let input = Input::from_text(text);
+            for part in solution.parts() {
+                let result = solution.solve(*part, &input);
+                assert!(result.is_ok(), "day {} part {}: {:?}", solution.day(), part, result.err());
+            }
+        }
+    }
+}
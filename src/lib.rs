@@ -0,0 +1,28 @@
+//! Advent of Code 2023 solutions.
+//!
+//! Each `dayN` module exposes its parser, parsed domain types and `part1`/`part2` solvers,
+//! plus a `DayN` value implementing [`solution::Solution`] that is listed in
+//! [`registry::SOLUTIONS`].
+
+pub mod answers;
+pub mod common;
+pub mod config;
+pub mod error;
+pub mod generate;
+pub mod logging;
+pub mod memory;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
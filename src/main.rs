@@ -1,82 +1,533 @@
-mod common;
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-
-use clap::{arg, command, value_parser, ArgAction, Command};
-use std::path::Path;
+use clap::parser::ValueSource;
+use clap::{arg, command, ArgAction, Command};
+use code_advent_2023::answers::{self, Answers, CheckStatus};
+use code_advent_2023::common::Input;
+use code_advent_2023::config::{self, Config};
+use code_advent_2023::memory::{self, CountingAllocator};
+use code_advent_2023::report::Record;
+use code_advent_2023::solution::Solution;
+use code_advent_2023::{generate, logging, registry, runner, scaffold};
+use std::panic::{self, AssertUnwindSafe};
+use std::{fs, thread};
+use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::process::exit;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn list_solutions() {
+    for solution in registry::SOLUTIONS {
+        let parts: Vec<String> = solution.parts().iter().map(|part| part.to_string()).collect();
+        println!("Day {:>2}: {:<32} parts {}", solution.day(), solution.title(), parts.join(", "));
+    }
+}
+
+/// One day/part to run, with its input already loaded (or the reason it couldn't be).
+#[derive(Clone)]
+struct Job {
+    solution: &'static dyn Solution,
+    part: u16,
+    /// Solver variant to use, or `None` for the part's default.
+    variant: Option<String>,
+    input_name: String,
+    input: Arc<Result<Input, String>>,
+}
+
+fn input_path(input_dir: &Path, day: u16, example: Option<u32>) -> PathBuf {
+    match example {
+        Some(n) => runner::example_input_path(input_dir, day, n),
+        None => runner::default_input_path(input_dir, day),
+    }
+}
+
+/// Every implemented part, using `variant` wherever a part has one by that name.
+///
+/// When checking examples, only the parts `answers` has an answer for are run: an example usually
+/// belongs to one part, and the other part may not even accept it as input.
+fn all_jobs(input_dir: &Path, example: Option<u32>, variant: Option<&str>, answers: Option<&Answers>) -> Vec<Job> {
+    registry::SOLUTIONS.iter().flat_map(|solution| {
+        let file_path = input_path(input_dir, solution.day(), example);
+        // most days only have one example, so a missing one isn't worth a failure row
+        let skip = example.is_some() && !file_path.exists();
+        let input_name = file_path.display().to_string();
+        let input = Arc::new(Input::from_path(&file_path).map_err(|err| err.to_string()));
+        let answers = answers.filter(|_| example.is_some());
+        let wanted: Vec<u16> = solution.parts().iter().copied()
+            .filter(|&part| !skip && answers.is_none_or(|answers| answers.expected(solution.day(), part, &input_name).is_some()))
+            .collect();
+        wanted.into_iter().map(move |part| Job {
+            solution: *solution,
+            part,
+            variant: variant.filter(|name| solution.variants(part).contains(name)).map(|name| name.to_string()),
+            input_name: input_name.clone(),
+            input: input.clone(),
+        })
+    }).collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+fn run_job(job: &Job) -> runner::RunResult {
+    match job.input.as_ref() {
+        Ok(input) => runner::run(job.solution, job.part, job.variant.as_deref(), input),
+        Err(message) => runner::RunResult::failed(job.solution.day(), job.part, message.clone()),
+    }
+}
+
+fn run_record(job: &Job, result: &runner::RunResult) -> Record {
+    let mut record = Record::new(result.day, result.part, &job.input_name);
+    record.variant = job.variant.clone();
+    record.duration_secs = Some(result.elapsed.as_secs_f64());
+    record.memory = result.memory;
+    match &result.answer {
+        Ok(answer) => record.set_answer(answer.clone()),
+        Err(message) => {
+            record.status = "error";
+            record.error = Some(message.clone());
+        }
+    }
+    record
+}
+
+fn run_all(jobs: &[Job], format: Format, workers: usize) -> bool {
+    // failures are reported in the table, so keep the default hook from spamming stderr
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut all_ok = true;
+    if format == Format::Text {
+        print!("{:>3}  {:>4}  {:<20}  {:>12}", "Day", "Part", "Answer", "Time");
+        if memory::is_enabled() {
+            print!("  {:>10}  {:>10}  {:>8}", "Peak", "Total", "Allocs");
+        }
+        println!();
+    }
+    runner::for_each_ordered(jobs, workers, run_job, |job, result| {
+        all_ok &= result.answer.is_ok();
+        match format {
+            Format::Json => println!("{}", run_record(job, &result).to_json()),
+            Format::Text => {
+                let answer = match &result.answer {
+                    Ok(answer) => answer.to_string(),
+                    Err(message) => format!("FAILED: {}", message),
+                };
+                print!("{:>3}  {:>4}  {:<20}  {:>12.3?}", result.day, result.part, answer, result.elapsed);
+                if let Some(stats) = result.memory {
+                    print!(
+                        "  {:>10}  {:>10}  {:>8}",
+                        memory::format_bytes(stats.peak_bytes), memory::format_bytes(stats.total_bytes), stats.allocations
+                    );
+                }
+                println!();
+            }
+        }
+    });
+
+    let _ = panic::take_hook();
+    all_ok
+}
+
+fn check(jobs: &[Job], answers: &Answers, format: Format, workers: usize) -> bool {
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut all_ok = true;
+    if format == Format::Text {
+        println!("{:>3}  {:>4}  {:<7}  Details", "Day", "Part", "Status");
+    }
+    runner::for_each_ordered(jobs, workers, run_job, |job, result| {
+        let expected = answers.expected(result.day, result.part, &job.input_name);
+        let status = answers::check(expected, &result.answer);
+        all_ok &= !status.is_failure();
+
+        let mut record = run_record(job, &result);
+        record.expected = expected.cloned();
+        let actual = result.answer.as_ref().map(|answer| answer.to_string()).unwrap_or_default();
+        let details = match &status {
+            CheckStatus::Pass => {
+                record.status = "pass";
+                actual
+            },
+            CheckStatus::Fail { expected, actual } => {
+                record.status = "fail";
+                format!("expected {}, got {}", expected, actual)
+            },
+            CheckStatus::Missing => {
+                record.status = "missing";
+                format!("no answer for {} (got {})", job.input_name, actual)
+            },
+            CheckStatus::Unsolved => {
+                record.status = "unsolved";
+                "part is not solved yet".to_string()
+            },
+            CheckStatus::Error(message) => message.clone(),
+        };
+        match format {
+            Format::Json => println!("{}", record.to_json()),
+            Format::Text => {
+                let label = if record.status == "error" { "FAIL".to_string() } else { record.status.to_uppercase() };
+                println!("{:>3}  {:>4}  {:<7}  {}", result.day, result.part, label, details);
+            }
+        }
+    });
+
+    let _ = panic::take_hook();
+    all_ok
+}
+
+fn bench(jobs: &[Job], iterations: usize, format: Format, workers: usize) -> bool {
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut all_ok = true;
+    if format == Format::Text {
+        println!(
+            "{:>3}  {:>4}  {:<5}  {:>12}  {:>12}  {:>12}  {:>12}",
+            "Day", "Part", "Step", "Min", "Median", "Mean", "Max"
+        );
+    }
+    let bench_job = |job: &Job| match job.input.as_ref() {
+        Ok(input) => runner::bench(job.solution, job.part, job.variant.as_deref(), input, iterations),
+        Err(message) => Err(message.clone()),
+    };
+    runner::for_each_ordered(jobs, workers, bench_job, |job, result| {
+        let mut record = Record::new(job.solution.day(), job.part, &job.input_name);
+        record.variant = job.variant.clone();
+        match &result {
+            Ok(result) => {
+                record.set_answer(result.answer.clone());
+                record.duration_secs = Some((result.parse.mean + result.solve.mean).as_secs_f64());
+                record.parse = Some(result.parse.into());
+                record.solve = Some(result.solve.into());
+            },
+            Err(message) => {
+                all_ok = false;
+                record.status = "error";
+                record.error = Some(message.clone());
+            }
+        }
+
+        match (format, result) {
+            (Format::Json, _) => println!("{}", record.to_json()),
+            (Format::Text, Ok(result)) => {
+                for (step, stats) in [("parse", result.parse), ("solve", result.solve)] {
+                    println!(
+                        "{:>3}  {:>4}  {:<5}  {:>12.3?}  {:>12.3?}  {:>12.3?}  {:>12.3?}",
+                        result.day, result.part, step, stats.min, stats.median, stats.mean, stats.max
+                    );
+                }
+            },
+            (Format::Text, Err(message)) => {
+                println!("{:>3}  {:>4}  FAILED: {}", job.solution.day(), job.part, message);
+            }
+        }
+    });
+
+    let _ = panic::take_hook();
+    all_ok
+}
+
+/// Runs every variant of each part and fails if any two disagree. Parts with a single solver have
+/// nothing to compare and are left out.
+fn cross_check(jobs: &[Job], format: Format, workers: usize) -> bool {
+    panic::set_hook(Box::new(|_| {}));
+
+    let variant_jobs: Vec<Job> = jobs.iter().flat_map(|job| {
+        job.solution.variants(job.part).into_iter().map(move |variant| Job {
+            variant: Some(variant.to_string()),
+            ..job.clone()
+        })
+    }).collect();
+    let mut results: Vec<(Job, runner::RunResult)> = Vec::new();
+    runner::for_each_ordered(&variant_jobs, workers, run_job, |job, result| results.push((job.clone(), result)));
+
+    let mut all_ok = true;
+    if format == Format::Text {
+        println!("{:>3}  {:>4}  {:<8}  {:<8}  {:<20}  {:>12}", "Day", "Part", "Variant", "Status", "Answer", "Time");
+    }
+    for group in results.chunk_by(|(a, _), (b, _)| a.solution.day() == b.solution.day() && a.part == b.part) {
+        let answers: Vec<_> = group.iter().map(|(_, result)| &result.answer).collect();
+        let failed = answers.iter().any(|answer| answer.is_err());
+        let agree = !failed && answers.windows(2).all(|pair| match (pair[0], pair[1]) {
+            (Ok(a), Ok(b)) => a.matches(b) || (!a.is_solved() && !b.is_solved()),
+            _ => false,
+        });
+        all_ok &= agree;
+
+        for (job, result) in group {
+            let mut record = run_record(job, result);
+            if result.answer.is_ok() {
+                record.status = if agree { "agree" } else { "mismatch" };
+            }
+            match format {
+                Format::Json => println!("{}", record.to_json()),
+                Format::Text => {
+                    let answer = match &result.answer {
+                        Ok(answer) => answer.to_string(),
+                        Err(message) => format!("FAILED: {}", message),
+                    };
+                    println!(
+                        "{:>3}  {:>4}  {:<8}  {:<8}  {:<20}  {:>12.3?}",
+                        result.day, result.part, job.variant.as_deref().unwrap_or_default(),
+                        record.status.to_uppercase(), answer, result.elapsed
+                    );
+                }
+            }
+        }
+    }
+
+    let _ = panic::take_hook();
+    all_ok
+}
+
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+fn watch_once(solution: &dyn Solution, part: u16, variant: Option<&str>, path: &Path) {
+    let input = match Input::from_path(path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("{}", err.diagnostic());
+            return;
+        }
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| solution.run_variant(part, variant, &input))) {
+        Ok(Ok(run)) => println!(
+            "Day {}, part {}: {}  ({:.3?} parse, {:.3?} solve)",
+            solution.day(), part, run.answer, run.parse_time, run.solve_time
+        ),
+        Ok(Err(err)) => eprintln!("{}", err.with_file(input.name()).diagnostic()),
+        Err(payload) => eprintln!("error: solver panicked: {}", runner::panic_message(payload)),
+    }
+}
+
+/// Re-runs the part every time the input file's modification time or size changes. Never returns;
+/// stop it with Ctrl-C.
+fn watch(solution: &dyn Solution, part: u16, variant: Option<&str>, path: &Path) -> ! {
+    let mut last_seen = None;
+    let mut first = true;
+    loop {
+        let seen = fs::metadata(path).and_then(|meta| Ok((meta.modified()?, meta.len()))).ok();
+        if first || seen != last_seen {
+            match seen {
+                Some(_) if first => eprintln!("watching {} (Ctrl-C to stop)", path.display()),
+                Some(_) => eprintln!("--- {} changed ---", path.display()),
+                None => eprintln!("waiting for {} to exist", path.display()),
+            }
+            if seen.is_some() {
+                watch_once(solution, part, variant, path);
+            }
+            last_seen = seen;
+            first = false;
+        }
+        thread::sleep(WATCH_POLL_INTERVAL);
+    }
+}
 
 fn main() {
     let matches = command!()
-        .arg(arg!(-d --day <day>).required(true).value_parser(clap::value_parser!(u16).range(1..=25)))
-        .arg(arg!(-p --part <part>).required(true).value_parser(clap::value_parser!(u16).range(1..=2)))
-        .arg(arg!(-i --input <input>).required(true))
+        .arg(arg!(-d --day <day>).required_unless_present_any(["list", "all"]).value_parser(clap::value_parser!(u16).range(1..=25)))
+        .arg(arg!(-p --part <part>).required_unless_present_any(["list", "all"]).value_parser(clap::value_parser!(u16).range(1..=2)))
+        .arg(arg!(-i --input <input> "Input file, or `-` for stdin [default: <input-dir>/dayNN.txt]"))
+        .arg(arg!(--"input-text" <text> "Inline puzzle input").conflicts_with("input"))
+        .arg(arg!(--example [n] "Use <input-dir>/examples/dayNN-N.txt instead").value_parser(clap::value_parser!(u32).range(1..))
+            .default_missing_value("1").conflicts_with_all(["input", "input-text"]))
+        .arg(arg!(--list "List the implemented days and parts").action(ArgAction::SetTrue))
+        .arg(arg!(--all "Run every implemented day and part").action(ArgAction::SetTrue).conflicts_with_all(["day", "part", "input", "input-text"]))
+        .arg(arg!(--"input-dir" <dir> "Directory holding dayNN.txt inputs [default: inputs]").env("AOC_INPUT_DIR"))
+        .arg(arg!(--config <file> "Settings file").default_value(config::DEFAULT_CONFIG_PATH))
+        .arg(arg!(--check [answers] "Compare answers against an answers file").default_missing_value("answers.toml").conflicts_with("bench"))
+        .arg(arg!(--bench <iterations> "Run N times and report parse and solve timings").value_parser(clap::value_parser!(u32).range(1..)))
+        .arg(arg!(--format <format> "Output format").value_parser(["text", "json"]).default_value("text"))
+        .arg(arg!(--mem "Report peak heap, bytes allocated and allocation count per run").action(ArgAction::SetTrue)
+            .conflicts_with_all(["bench", "watch"]))
+        .arg(arg!(--variant <name> "Solver variant to run, e.g. naive or fast"))
+        .arg(arg!(--"cross-check" "Run every solver variant and fail if their answers differ").action(ArgAction::SetTrue)
+            .conflicts_with_all(["variant", "bench", "check", "watch"]))
+        .arg(arg!(--watch "Re-run whenever the input file changes").action(ArgAction::SetTrue)
+            .conflicts_with_all(["all", "input-text", "bench", "check"]))
+        .arg(arg!(-j --jobs <n> "Run up to N solvers in parallel").value_parser(clap::value_parser!(u32).range(1..)).default_value("1"))
+        .arg(arg!(-v --verbose... "Log more to stderr (-v info, -vv debug, -vvv trace)"))
+        .arg(arg!(-q --quiet "Only log errors").action(ArgAction::SetTrue).conflicts_with("verbose"))
+        .subcommand_negates_reqs(true)
+        .subcommand(Command::new("new-day").about("Write src/dayN.rs from a template and register it")
+            .arg(arg!(<day> "Day number").value_parser(clap::value_parser!(u16).range(1..=25)))
+            .arg(arg!(--root <dir> "Crate root to scaffold into").default_value(".")))
+        .subcommand(Command::new("generate").about("Print a synthetic input for stress-testing a day")
+            .arg(arg!(<day> "Day number").value_parser(clap::value_parser!(u16).range(1..=25)))
+            .arg(arg!(--seed <n> "Random seed; the same seed always gives the same input").value_parser(clap::value_parser!(u64)).default_value("1"))
+            .arg(arg!(--size <n> "Scale of the input [default: depends on the day; at most 14 for day 8]").value_parser(clap::value_parser!(usize))))
         .get_matches();
-    let file_path: &Path = Path::new(matches.get_one::<String>("input").unwrap());
+
+    logging::init(logging::level_for(matches.get_count("verbose"), matches.get_flag("quiet")));
+
+    if let Some(("new-day", sub_matches)) = matches.subcommand() {
+        let day = *sub_matches.get_one::<u16>("day").unwrap();
+        let root = Path::new(sub_matches.get_one::<String>("root").unwrap());
+        match scaffold::new_day(root, day) {
+            Ok(paths) => {
+                for path in paths {
+                    println!("wrote {}", path.display());
+                }
+            },
+            Err(err) => {
+                eprintln!("{}", err.diagnostic());
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(("generate", sub_matches)) = matches.subcommand() {
+        let day = *sub_matches.get_one::<u16>("day").unwrap();
+        let seed = *sub_matches.get_one::<u64>("seed").unwrap();
+        let size = sub_matches.get_one::<usize>("size").copied().unwrap_or_else(|| generate::default_size(day));
+        match generate::generate(day, seed, size) {
+            Ok(text) => print!("{}", text),
+            Err(err) => {
+                eprintln!("{}", err.diagnostic());
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    if matches.get_flag("list") {
+        list_solutions();
+        return;
+    }
+
+    let config_path = matches.get_one::<String>("config").unwrap();
+    let config = if matches.value_source("config") == Some(ValueSource::DefaultValue) {
+        Config::load_optional(Path::new(config_path))
+    } else {
+        Config::load(Path::new(config_path))
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err.diagnostic());
+            exit(1);
+        }
+    };
+    let input_dir = match matches.get_one::<String>("input-dir") {
+        Some(dir) => Path::new(dir),
+        None => config.input_dir(),
+    };
+    let example = matches.get_one::<u32>("example").copied();
+
+    let format = match matches.get_one::<String>("format").map(|s| s.as_str()) {
+        Some("json") => Format::Json,
+        _ => Format::Text,
+    };
+    if matches.get_flag("mem") {
+        memory::enable();
+    }
+    let workers = *matches.get_one::<u32>("jobs").unwrap() as usize;
+    let variant = matches.get_one::<String>("variant").map(|s| s.as_str());
+    let cross_checking = matches.get_flag("cross-check");
+    let bench_iterations = matches.get_one::<u32>("bench").map(|n| *n as usize);
+    let answers = match matches.get_one::<String>("check") {
+        Some(path) => match Answers::load(Path::new(path)) {
+            Ok(answers) => Some(answers),
+            Err(err) => {
+                eprintln!("{}", err.diagnostic());
+                exit(1);
+            }
+        },
+        None => None,
+    };
+
+    if matches.get_flag("all") {
+        let jobs = all_jobs(input_dir, example, variant, answers.as_ref());
+        let ok = match (bench_iterations, &answers) {
+            _ if cross_checking => cross_check(&jobs, format, workers),
+            (Some(iterations), _) => bench(&jobs, iterations, format, workers),
+            (None, Some(answers)) => check(&jobs, answers, format, workers),
+            (None, None) => run_all(&jobs, format, workers),
+        };
+        if !ok {
+            exit(1);
+        }
+        return;
+    }
+
     let day: u16 = *matches.get_one::<u16>("day").unwrap();
     let part: u16 = *matches.get_one::<u16>("part").unwrap();
 
-    println!("Day {}, part {}: ", day, part);
+    let solution = match registry::find(day) {
+        Some(solution) => solution,
+        None => {
+            eprintln!("Day {} is not implemented yet (see --list)", day);
+            exit(1);
+        }
+    };
 
-    let result: String = match day {
-        1 => match part {
-            1 => day1::part1(file_path),
-            2 => day1::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        2 => match part {
-            1 => day2::part1(file_path),
-            2 => day2::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        3 => match part {
-            1 => day3::part1(file_path),
-            2 => day3::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        4 => match part {
-            1 => day4::part1(file_path),
-            2 => day4::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        5 => match part {
-            1 => day5::part1(file_path),
-            2 => day5::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        6 => match part {
-            1 => day6::part1(file_path),
-            2 => day6::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        7 => match part {
-            1 => day7::part1(file_path),
-            2 => day7::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        8 => match part {
-            1 => day8::part1(file_path),
-            2 => day8::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        9 => match part {
-            1 => day9::part1(file_path),
-            2 => day9::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        10 => match part {
-            1 => day10::part1(file_path),
-            2 => day10::part2(file_path),
-            _ => panic!("Unknown part {}", part),
-        },
-        _ => panic!("unknown day {}", day)
+    if !solution.parts().contains(&part) {
+        eprintln!("Day {}, part {} is not implemented yet (see --list)", day, part);
+        exit(1);
+    }
+
+    if cross_checking && solution.variants(part).is_empty() {
+        eprintln!("Day {}, part {} has a single solver, so there's nothing to cross-check", day, part);
+        exit(1);
+    }
+
+    if matches.get_flag("watch") {
+        let path = match matches.get_one::<String>("input") {
+            Some(path) if path == "-" => {
+                eprintln!("--watch needs an input file, not stdin");
+                exit(1);
+            },
+            Some(path) => PathBuf::from(path),
+            None => input_path(input_dir, day, example),
+        };
+        watch(solution, part, variant, &path);
+    }
+
+    let input = match (matches.get_one::<String>("input"), matches.get_one::<String>("input-text")) {
+        (_, Some(text)) => Ok(Input::from_text(text.as_str())),
+        (Some(path), None) if path == "-" => Input::stdin(),
+        (Some(path), None) => Input::from_path(Path::new(path)),
+        (None, None) => Input::from_path(&input_path(input_dir, day, example)),
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("{}", err.diagnostic());
+            exit(1);
+        }
     };
-    println!("Result: {}", result);
+
+    if bench_iterations.is_some() || answers.is_some() || format == Format::Json || memory::is_enabled() || cross_checking {
+        let job = Job {
+            solution,
+            part,
+            variant: variant.map(|name| name.to_string()),
+            input_name: input.name().to_string(),
+            input: Arc::new(Ok(input)),
+        };
+        let ok = match (bench_iterations, &answers) {
+            _ if cross_checking => cross_check(&[job], format, workers),
+            (Some(iterations), _) => bench(&[job], iterations, format, workers),
+            (None, Some(answers)) => check(&[job], answers, format, workers),
+            (None, None) => run_all(&[job], format, workers),
+        };
+        if !ok {
+            exit(1);
+        }
+        return;
+    }
+
+    println!("Day {}, part {}: ", day, part);
+
+    match solution.run_variant(part, variant, &input) {
+        Ok(run) => println!("Result: {}", run.answer),
+        Err(err) => {
+            eprintln!("{}", err.with_file(input.name()).diagnostic());
+            exit(1);
+        }
+    }
 }
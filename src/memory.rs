@@ -0,0 +1,126 @@
+//! Heap accounting for `--mem`.
+//!
+//! The binary installs [`CountingAllocator`] as its global allocator. It forwards to the system
+//! allocator and, once [`enable`] has been called, keeps per-thread counts so that a solver run on
+//! a `--jobs` worker is measured on its own.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::Serialize;
+
+pub struct CountingAllocator;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // const-initialized and free of destructors, so touching these never allocates
+    static CURRENT: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+    static TOTAL: Cell<usize> = const { Cell::new(0) };
+    static COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+fn record_alloc(size: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = CURRENT.try_with(|current| {
+        current.set(current.get() + size as isize);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(current.get())));
+    });
+    let _ = TOTAL.try_with(|total| total.set(total.get() + size));
+    let _ = COUNT.try_with(|count| count.set(count.get() + 1));
+}
+
+fn record_dealloc(size: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = CURRENT.try_with(|current| current.set(current.get() - size as isize));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Turns counting on. Until this is called the allocator is a plain pass-through.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Heap use of one measured run. The peak is relative to what was live when the run started.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MemStats {
+    pub peak_bytes: usize,
+    pub total_bytes: usize,
+    pub allocations: usize,
+}
+
+/// Runs `f` and reports the heap activity it caused on this thread, or `None` when counting is off.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<MemStats>) {
+    if !is_enabled() {
+        return (f(), None);
+    }
+
+    CURRENT.with(|current| current.set(0));
+    PEAK.with(|peak| peak.set(0));
+    TOTAL.with(|total| total.set(0));
+    COUNT.with(|count| count.set(0));
+
+    let result = f();
+
+    let stats = MemStats {
+        peak_bytes: PEAK.with(|peak| peak.get()).max(0) as usize,
+        total_bytes: TOTAL.with(|total| total.get()),
+        allocations: COUNT.with(|count| count.get()),
+    };
+    (result, Some(stats))
+}
+
+/// Renders a byte count with a binary unit, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
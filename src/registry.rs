@@ -0,0 +1,19 @@
+use crate::solution::Solution;
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10};
+
+pub static SOLUTIONS: &[&dyn Solution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+];
+
+pub fn find(day: u16) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().find(|solution| solution.day() == day).copied()
+}
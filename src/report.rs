@@ -0,0 +1,79 @@
+//! Machine-readable records for `--format json`, one JSON object per line.
+
+use serde::Serialize;
+use crate::memory::MemStats;
+use crate::runner::Stats;
+use crate::solution::Answer;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsRecord {
+    pub min_secs: f64,
+    pub median_secs: f64,
+    pub mean_secs: f64,
+    pub max_secs: f64,
+}
+
+impl From<Stats> for StatsRecord {
+    fn from(stats: Stats) -> Self {
+        StatsRecord {
+            min_secs: stats.min.as_secs_f64(),
+            median_secs: stats.median.as_secs_f64(),
+            mean_secs: stats.mean.as_secs_f64(),
+            max_secs: stats.max.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub day: u16,
+    pub part: u16,
+    pub input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub answer: Option<Answer>,
+    /// `ok`, `unsolved` or `error` for plain runs, `pass`, `fail`, `missing`, `unsolved` or `error`
+    /// for `--check`, and `agree`, `mismatch` or `error` for `--cross-check`.
+    pub status: &'static str,
+    pub duration_secs: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse: Option<StatsRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solve: Option<StatsRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemStats>,
+    pub error: Option<String>,
+}
+
+impl Record {
+    pub fn new(day: u16, part: u16, input: &str) -> Record {
+        Record {
+            day,
+            part,
+            input: input.to_string(),
+            variant: None,
+            answer: None,
+            status: "ok",
+            duration_secs: None,
+            expected: None,
+            parse: None,
+            solve: None,
+            memory: None,
+            error: None,
+        }
+    }
+
+    /// Records a successful run, marking stubbed parts as `unsolved`.
+    pub fn set_answer(&mut self, answer: Answer) {
+        if !answer.is_solved() {
+            self.status = "unsolved";
+        }
+        self.answer = Some(answer);
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("records only contain plain data")
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::common::Input;
+use crate::memory::{self, MemStats};
+use crate::solution::{Answer, PartRun, Solution};
+
+pub struct RunResult {
+    pub day: u16,
+    pub part: u16,
+    pub answer: Result<Answer, String>,
+    pub elapsed: Duration,
+    /// Heap use of the run, when `--mem` turned counting on.
+    pub memory: Option<MemStats>,
+}
+
+impl RunResult {
+    pub fn failed(day: u16, part: u16, message: String) -> RunResult {
+        RunResult {
+            day,
+            part,
+            answer: Err(message),
+            elapsed: Duration::ZERO,
+            memory: None,
+        }
+    }
+}
+
+pub fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "solver panicked".to_string()
+    }
+}
+
+/// Runs one part, catching panics so a single broken solver doesn't take down a whole batch.
+fn run_part(solution: &dyn Solution, part: u16, variant: Option<&str>, input: &Input) -> Result<PartRun, String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| solution.run_variant(part, variant, input))) {
+        Ok(Ok(run)) => Ok(run),
+        Ok(Err(err)) => Err(err.with_file(input.name()).to_string()),
+        Err(payload) => Err(panic_message(payload)),
+    }
+}
+
+/// Runs one part, using the named variant's solver when `variant` is given.
+pub fn run(solution: &dyn Solution, part: u16, variant: Option<&str>, input: &Input) -> RunResult {
+    let start = Instant::now();
+    let (answer, memory) = memory::measure(|| run_part(solution, part, variant, input).map(|run| run.answer));
+
+    RunResult {
+        day: solution.day(),
+        part,
+        answer,
+        elapsed: start.elapsed(),
+        memory,
+    }
+}
+
+/// Summary of a set of timing samples.
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Returns `None` for an empty sample set.
+    pub fn from_samples(samples: &[Duration]) -> Option<Stats> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let count = sorted.len();
+        if count == 0 {
+            return None;
+        }
+
+        let median = if count % 2 == 1 {
+            sorted[count / 2]
+        } else {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+        };
+        Some(Stats {
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / count as u32,
+            max: sorted[count - 1],
+        })
+    }
+}
+
+pub struct BenchResult {
+    pub day: u16,
+    pub part: u16,
+    pub answer: Answer,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Runs one part `iterations` times, timing the parse and solve steps separately.
+pub fn bench(solution: &dyn Solution, part: u16, variant: Option<&str>, input: &Input, iterations: usize) -> Result<BenchResult, String> {
+    let mut answer = Answer::Unsolved;
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut solve_samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let run = run_part(solution, part, variant, input)?;
+        parse_samples.push(run.parse_time);
+        solve_samples.push(run.solve_time);
+        answer = run.answer;
+    }
+
+    match (Stats::from_samples(&parse_samples), Stats::from_samples(&solve_samples)) {
+        (Some(parse), Some(solve)) => Ok(BenchResult {
+            day: solution.day(),
+            part,
+            answer,
+            parse,
+            solve,
+        }),
+        _ => Err("benchmark needs at least one iteration".to_string()),
+    }
+}
+
+/// Runs `work` over `items` on up to `workers` threads, handing each result to `emit` in the
+/// original order as soon as it and everything before it are done.
+///
+/// Each call to `work` runs start to finish on one thread, so timings taken inside it aren't
+/// affected by how long the item waited in the queue.
+pub fn for_each_ordered<T, R>(items: &[T], workers: usize, work: impl Fn(&T) -> R + Sync, mut emit: impl FnMut(&T, R))
+where
+    T: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if sender.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished = HashMap::new();
+        let mut next_to_emit = 0;
+        for (index, result) in receiver {
+            finished.insert(index, result);
+            while let Some(result) = finished.remove(&next_to_emit) {
+                emit(&items[next_to_emit], result);
+                next_to_emit += 1;
+            }
+        }
+    });
+}
+
+/// Input file for a day under the conventional layout, e.g. `inputs/day07.txt`.
+pub fn default_input_path(input_dir: &Path, day: u16) -> PathBuf {
+    input_dir.join(format!("day{:02}.txt", day))
+}
+
+/// Example `n` from a day's puzzle statement, e.g. `inputs/examples/day07-1.txt`.
+pub fn example_input_path(input_dir: &Path, day: u16, n: u32) -> PathBuf {
+    input_dir.join("examples").join(format!("day{:02}-{}.txt", day, n))
+}
@@ -0,0 +1,171 @@
+//! `new-day N`: writes `src/dayN.rs` from a template and registers it in `lib.rs` and
+//! `registry.rs`, so the new day runs with `-d N` straight away. Its stubbed parts answer
+//! [`Answer::Unsolved`](crate::solution::Answer::Unsolved) until they're filled in.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+
+const TEMPLATE: &str = r#"use crate::common::{Input, Line};
+use crate::error::Result;
+use crate::solution::{Answer, Puzzle};
+
+pub fn parse_input(input: &Input) -> Result<Vec<Line>> {
+    Ok(input.trimmed_lines())
+}
+
+pub fn part1(input: &Input) -> Result<Answer> {
+    solve_part1(&parse_input(input)?)
+}
+
+pub fn solve_part1(_lines: &[Line]) -> Result<Answer> {
+    Ok(Answer::Unsolved)
+}
+
+pub fn part2(input: &Input) -> Result<Answer> {
+    solve_part2(&parse_input(input)?)
+}
+
+pub fn solve_part2(_lines: &[Line]) -> Result<Answer> {
+    Ok(Answer::Unsolved)
+}
+
+pub struct Day{N};
+
+impl Puzzle for Day{N} {
+    const DAY: u16 = {N};
+    const TITLE: &'static str = "Day {N}";
+
+    type Part1Input = Vec<Line>;
+    type Part2Input = Vec<Line>;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input> {
+        parse_input(input)
+    }
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer> {
+        solve_part1(parsed)
+    }
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input> {
+        parse_input(input)
+    }
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer> {
+        solve_part2(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+";
+
+    #[test]
+    #[ignore = "fill in the example from the puzzle statement"]
+    fn part1_example() {
+        assert_eq!(part1(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(0));
+    }
+
+    #[test]
+    #[ignore = "fill in the example from the puzzle statement"]
+    fn part2_example() {
+        assert_eq!(part2(&Input::from_text(EXAMPLE)).unwrap(), Answer::from(0));
+    }
+}
+"#;
+
+pub fn render_day(day: u16) -> String {
+    TEMPLATE.replace("{N}", &day.to_string())
+}
+
+/// Adds `pub mod dayN;` after the last day module declared in `lib.rs`.
+pub fn register_in_lib(lib_rs: &str, day: u16) -> std::result::Result<String, String> {
+    let declaration = format!("pub mod day{};", day);
+    if lib_rs.lines().any(|line| line.trim() == declaration) {
+        return Err(format!("day{} is already declared in lib.rs", day));
+    }
+    let mut lines: Vec<&str> = lib_rs.lines().collect();
+    let last_day = lines.iter().rposition(|line| line.starts_with("pub mod day"))
+        .ok_or("no `pub mod dayN;` lines in lib.rs")?;
+    lines.insert(last_day + 1, &declaration);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds the day to the `use crate::{...}` list and the end of `SOLUTIONS` in `registry.rs`.
+pub fn register_in_registry(registry_rs: &str, day: u16) -> std::result::Result<String, String> {
+    let module = format!("day{}", day);
+    let entry = format!("    &{}::Day{},", module, day);
+    if registry_rs.lines().any(|line| line == entry) {
+        return Err(format!("{} is already in SOLUTIONS", module));
+    }
+
+    let mut lines: Vec<String> = registry_rs.lines().map(|line| line.to_string()).collect();
+    let uses = lines.iter_mut().find(|line| line.starts_with("use crate::{day"))
+        .ok_or("no `use crate::{day...}` line in registry.rs")?;
+    *uses = uses.replace("};", &format!(", {}}};", module));
+
+    let start = lines.iter().position(|line| line.starts_with("pub static SOLUTIONS"))
+        .ok_or("no SOLUTIONS list in registry.rs")?;
+    let end = start + lines[start..].iter().position(|line| line == "];")
+        .ok_or("SOLUTIONS list in registry.rs isn't closed")?;
+    lines.insert(end, entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        name: path.display().to_string(),
+        source,
+    })
+}
+
+fn write(path: &Path, text: &str) -> Result<()> {
+    fs::write(path, text).map_err(|source| Error::Io {
+        name: path.display().to_string(),
+        source,
+    })
+}
+
+/// Scaffolds day `day` in the crate rooted at `root`, returning the files it touched. Nothing is
+/// written unless every edit succeeds.
+pub fn new_day(root: &Path, day: u16) -> Result<Vec<PathBuf>> {
+    let src = root.join("src");
+    let day_path = src.join(format!("day{}.rs", day));
+    let lib_path = src.join("lib.rs");
+    let registry_path = src.join("registry.rs");
+
+    if day_path.exists() {
+        return Err(Error::Config(format!("{} already exists", day_path.display())));
+    }
+    let lib_rs = register_in_lib(&read(&lib_path)?, day)
+        .map_err(|message| Error::Config(format!("{}: {}", lib_path.display(), message)))?;
+    let registry_rs = register_in_registry(&read(&registry_path)?, day)
+        .map_err(|message| Error::Config(format!("{}: {}", registry_path.display(), message)))?;
+
+    write(&day_path, &render_day(day))?;
+    write(&lib_path, &lib_rs)?;
+    write(&registry_path, &registry_rs)?;
+    Ok(vec![day_path, lib_path, registry_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_after_the_last_day() {
+        let lib_rs = "pub mod common;\npub mod day1;\npub mod day2;\n";
+        assert_eq!(register_in_lib(lib_rs, 3).unwrap(), "pub mod common;\npub mod day1;\npub mod day2;\npub mod day3;\n");
+        assert!(register_in_lib(lib_rs, 2).is_err());
+
+        let registry_rs = "use crate::{day1, day2};\n\npub static SOLUTIONS: &[&dyn Solution] = &[\n    &day1::Day1,\n    &day2::Day2,\n];\n";
+        assert_eq!(
+            register_in_registry(registry_rs, 3).unwrap(),
+            "use crate::{day1, day2, day3};\n\npub static SOLUTIONS: &[&dyn Solution] = &[\n    &day1::Day1,\n    &day2::Day2,\n    &day3::Day3,\n];\n"
+        );
+        assert!(register_in_registry(registry_rs, 2).is_err());
+    }
+}
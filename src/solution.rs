@@ -0,0 +1,205 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use serde::{Serialize, Serializer};
+use crate::common::Input;
+use crate::error::{Error, Result};
+
+/// A single day's puzzle, split into a parse step and a solve step per part so the two can be
+/// timed separately. Every `Puzzle` is usable as a [`Solution`].
+pub trait Puzzle {
+    const DAY: u16;
+    const TITLE: &'static str;
+    /// Parts that have a real implementation. Days with a stubbed part override this.
+    const PARTS: &'static [u16] = &[1, 2];
+
+    type Part1Input;
+    type Part2Input;
+
+    fn parse_part1(input: &Input) -> Result<Self::Part1Input>;
+
+    fn solve_part1(parsed: &Self::Part1Input) -> Result<Answer>;
+
+    fn parse_part2(input: &Input) -> Result<Self::Part2Input>;
+
+    fn solve_part2(parsed: &Self::Part2Input) -> Result<Answer>;
+
+    /// Named alternatives for part 1, e.g. a naive solver kept next to a fast one, selectable
+    /// with `--variant`. `solve_part1` is still what runs when no variant is asked for.
+    fn part1_variants() -> Vec<Variant<Self::Part1Input>> {
+        Vec::new()
+    }
+
+    /// Named alternatives for part 2, as for [`Puzzle::part1_variants`].
+    fn part2_variants() -> Vec<Variant<Self::Part2Input>> {
+        Vec::new()
+    }
+}
+
+/// A named solver for one part.
+pub type Variant<T> = (&'static str, fn(&T) -> Result<Answer>);
+
+/// What a solver produced for one part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// The part is stubbed out and has no real answer yet.
+    Unsolved,
+}
+
+impl Answer {
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved)
+    }
+
+    /// Whether two answers are the same, comparing numbers as numbers. An integer matches text
+    /// holding the same digits, since answers files may quote large numbers.
+    pub fn matches(&self, other: &Answer) -> bool {
+        match (self, other) {
+            (Answer::Unsolved, _) | (_, Answer::Unsolved) => false,
+            (Answer::Integer(a), Answer::Integer(b)) => a == b,
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (Answer::Integer(n), Answer::Text(s)) | (Answer::Text(s), Answer::Integer(n)) => s.trim().parse() == Ok(*n),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "(unsolved)"),
+        }
+    }
+}
+
+/// Integers stay numbers in JSON, text becomes a string and unsolved parts are `null`.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Answer::Integer(n) => serializer.serialize_i128(*n),
+            Answer::Text(s) => serializer.serialize_str(s),
+            Answer::Unsolved => serializer.serialize_none(),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Integer(n as i128)
+            }
+        })*
+    };
+}
+
+integer_answer!(i32, i64, i128, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// The answer to one part along with how long each step took.
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Object-safe view of a [`Puzzle`], registered in `registry::SOLUTIONS`.
+pub trait Solution: Sync {
+    fn day(&self) -> u16;
+
+    fn title(&self) -> &'static str;
+
+    fn parts(&self) -> &'static [u16];
+
+    /// Names of the part's variants, empty when it only has the one solver.
+    fn variants(&self, part: u16) -> Vec<&'static str>;
+
+    /// Runs the named variant of a part, or its default solver for `None`.
+    fn run_variant(&self, part: u16, variant: Option<&str>, input: &Input) -> Result<PartRun>;
+
+    fn run_part(&self, part: u16, input: &Input) -> Result<PartRun> {
+        self.run_variant(part, None, input)
+    }
+
+    fn solve(&self, part: u16, input: &Input) -> Result<Answer> {
+        self.run_part(part, input).map(|run| run.answer)
+    }
+}
+
+fn timed<T>(input: &Input, parse: fn(&Input) -> Result<T>, solve: fn(&T) -> Result<Answer>) -> Result<PartRun> {
+    let start = Instant::now();
+    let parsed = parse(input)?;
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    let answer = solve(&parsed)?;
+    let solve_time = start.elapsed();
+
+    Ok(PartRun {
+        answer,
+        parse_time,
+        solve_time,
+    })
+}
+
+fn pick_variant<T>(
+    day: u16,
+    part: u16,
+    variants: Vec<Variant<T>>,
+    name: Option<&str>,
+    default: fn(&T) -> Result<Answer>,
+) -> Result<fn(&T) -> Result<Answer>> {
+    let Some(name) = name else {
+        return Ok(default);
+    };
+    let names: Vec<&str> = variants.iter().map(|(name, _)| *name).collect();
+    variants.iter().find(|(variant, _)| *variant == name).map(|(_, solve)| *solve).ok_or_else(|| {
+        let available = if names.is_empty() { "none".to_string() } else { names.join(", ") };
+        Error::Solve(format!("day {} part {} has no `{}` variant (available: {})", day, part, name, available))
+    })
+}
+
+impl<P: Puzzle + Sync> Solution for P {
+    fn day(&self) -> u16 {
+        P::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn parts(&self) -> &'static [u16] {
+        P::PARTS
+    }
+
+    fn variants(&self, part: u16) -> Vec<&'static str> {
+        match part {
+            1 => P::part1_variants().iter().map(|(name, _)| *name).collect(),
+            _ => P::part2_variants().iter().map(|(name, _)| *name).collect(),
+        }
+    }
+
+    fn run_variant(&self, part: u16, variant: Option<&str>, input: &Input) -> Result<PartRun> {
+        if !P::PARTS.contains(&part) {
+            return Err(Error::Solve(format!("day {} part {} is not implemented", P::DAY, part)));
+        }
+        match part {
+            1 => timed(input, P::parse_part1, pick_variant(P::DAY, part, P::part1_variants(), variant, P::solve_part1)?),
+            _ => timed(input, P::parse_part2, pick_variant(P::DAY, part, P::part2_variants(), variant, P::solve_part2)?),
+        }
+    }
+}
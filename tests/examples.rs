@@ -0,0 +1,33 @@
+//! Runs the binary over every example listed in answers.toml, the way the file's header says to.
+
+use std::collections::BTreeSet;
+use std::process::Command;
+
+/// The `N` of every `dayNN-N.txt` example answers.toml has an answer for.
+fn example_numbers() -> BTreeSet<u32> {
+    let text = std::fs::read_to_string("answers.toml").unwrap();
+    let days: toml::Table = toml::from_str(&text).unwrap();
+    days.values()
+        .flat_map(|inputs| inputs.as_table().unwrap().keys())
+        .filter_map(|input| input.strip_prefix("inputs/examples/")?.strip_suffix(".txt")?.rsplit_once('-')?.1.parse().ok())
+        .collect()
+}
+
+#[test]
+fn every_example_checks_out() {
+    let numbers = example_numbers();
+    assert!(!numbers.is_empty());
+    for n in numbers {
+        let output = Command::new(env!("CARGO_BIN_EXE_code-advent-2023"))
+            .args(["--all", "--example", &n.to_string(), "--check"])
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "--all --example {} --check failed:\n{}{}",
+            n,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
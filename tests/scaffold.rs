@@ -0,0 +1,51 @@
+//! Scaffolds a day into a copy of the crate and checks the copy builds and runs the new day.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+fn succeeded(what: &str, output: Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(output.status.success(), "{} failed:\n{}{}", what, stdout, String::from_utf8_lossy(&output.stderr));
+    stdout
+}
+
+#[test]
+fn new_day_runs_as_unsolved() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("scaffold");
+    let _ = fs::remove_dir_all(root.join("src"));
+    copy_dir(Path::new("src"), &root.join("src"));
+    for file in ["Cargo.toml", "Cargo.lock"] {
+        if Path::new(file).exists() {
+            fs::copy(file, root.join(file)).unwrap();
+        }
+    }
+
+    succeeded("new-day", Command::new(env!("CARGO_BIN_EXE_code-advent-2023"))
+        .args(["new-day", "25", "--root"])
+        .arg(&root)
+        .output()
+        .unwrap());
+
+    // the copy keeps its own target directory, so only the first run builds the dependencies
+    let run = succeeded("-d 25 -p 1", Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--offline", "--", "-d", "25", "-p", "1", "--input-text", "x"])
+        .current_dir(&root)
+        .env_remove("CARGO_TARGET_DIR")
+        .output()
+        .unwrap());
+    assert!(run.contains("(unsolved)"), "{}", run);
+}